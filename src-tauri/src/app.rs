@@ -1,50 +1,107 @@
-use crate::{core::{background, AppState}, commands, ui::{tray, window}, lol};
+use crate::{core::{background, config_watcher, config_writer, frontend::Frontend, instance::{self, Instance, InstanceServer}, state_sync, AppState}, commands, headless, integrations::{api, mqtt, overlay}, ui::{tray, window}, utils::{autostart, launch, logging}, lol};
 
 /// 应用程序入口点
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // 先初始化日志以记录配置加载过程，加载后再按配置调整级别
+    if let Err(e) = logging::init() {
+        eprintln!("{}", e);
+    }
+
+    // 同一配置目录只运行一个实例，再次启动时把参数交给运行中的实例
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let instance = match instance::acquire(&args) {
+        Ok(Instance::Primary(server)) => server,
+        Ok(Instance::Forwarded(message)) => {
+            println!("{}", message);
+            return;
+        }
+        Err(e) => {
+            tracing::error!("{}", e);
+            eprintln!("{}", e);
+            return;
+        }
+    };
+
     let app_state = AppState::new();
+    let log_level = app_state.read(|snapshot| snapshot.config.log_level.clone());
+    if let Err(e) = logging::set_level(&log_level) {
+        tracing::warn!("{}", e);
+    }
+
+    if launch::options().headless {
+        headless::run(instance, app_state);
+        return;
+    }
 
     tauri::Builder::default()
         .manage(app_state.clone())
         .setup(move |app| {
             window::setup_window(app, &app_state)?;
             
-            // 设置系统托盘
-            let config = app_state.config.lock().unwrap();
-            let mouse_through_state = config.mouse_through;
-            let auto_accept_state = config.auto_accept;
-            let auto_hide_state = config.auto_hide;
-            drop(config);
-            tray::create_tray(app, &app_state, mouse_through_state, auto_accept_state, auto_hide_state)?;
+            // 设置系统托盘，之后的状态变化由订阅任务同步到托盘和前端
+            tray::create_tray(app, &app_state)?;
+            state_sync::start(app.handle().clone(), app_state.clone());
             
-            start_background_task(app, &app_state);
+            start_services(Frontend::tauri(app.handle()), &app_state, instance);
             Ok(())
         })
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
         .invoke_handler(tauri::generate_handler![
             commands::get_app_state,
             commands::set_auto_accept,
+            commands::get_active_profile,
+            commands::set_champion_lists,
+            commands::set_rune_pages,
             commands::save_window_position,
             commands::save_window_visible,
+            commands::export_match_history,
+            commands::export_config,
+            commands::preview_config_import,
+            commands::import_config,
+            commands::export_diagnostics,
+            commands::reconnect_lcu,
+            commands::set_log_level,
             lol::check_admin_privileges,
             lol::get_lcu_auth,
             lol::get_summoner_info,
             lol::get_gameflow_phase,
+            lol::get_ranked_stats,
+            lol::get_player_profile,
             lol::accept_match
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
-/// 启动后台任务
-fn start_background_task(app: &tauri::App, app_state: &AppState) {
-    let app_handle = app.handle().clone();
-    let state_for_task = app_state.clone();
-    std::thread::spawn(move || {
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        rt.block_on(async {
-            background::background_task(app_handle, state_for_task).await;
-        });
-    });
-}
\ No newline at end of file
+/// 启动界面模式和无界面模式共用的服务：配置读写、单实例转发、本地控制接口和后台任务
+pub fn start_services(frontend: Frontend, app_state: &AppState, instance: InstanceServer) {
+    config_writer::start(frontend.clone(), app_state.clone());
+    config_watcher::start(frontend.clone(), app_state.clone());
+    instance.start(frontend.clone(), app_state.clone());
+    
+    let config = app_state.snapshot().config;
+    // 程序移动位置后重新注册开机自启，使其指向当前路径
+    if config.autostart {
+        if let Err(e) = autostart::set_enabled(true) {
+            tracing::warn!("更新开机自启失败: {}", e);
+        }
+    }
+    let api_config = config.api;
+    let overlay_config = config.overlay;
+    let mqtt_config = config.mqtt;
+    
+    // 按配置启动本地控制接口
+    if api_config.enabled {
+        api::start(frontend.clone(), app_state.clone(), api_config.port);
+    }
+    if overlay_config.enabled {
+        overlay::start(app_state.clone(), overlay_config.port);
+    }
+    if mqtt_config.enabled {
+        mqtt::start(app_state.clone(), mqtt_config);
+    }
+    
+    background::start(frontend, app_state.clone());
+}
@@ -0,0 +1,197 @@
+use crate::core::instance::{self, RECONNECT_COMMAND, STATUS_COMMAND};
+use crate::lol;
+use crate::utils::bundle;
+use crate::utils::export::{self, ExportFormat, DEFAULT_MATCH_LIMIT};
+use crate::utils::launch::{self, LaunchOptions};
+use crate::utils::AppConfig;
+use std::path::PathBuf;
+
+const EXPORT_USAGE: &str = "用法: micept export-matches --output <路径> [--format csv|json] [--limit <数量>]";
+const EXPORT_CONFIG_USAGE: &str =
+    "用法: micept export-config --output <路径> [--preset] [--preset-only] [--include-private]\n\
+--include-private 同时导出 MQTT 密码、Webhook 地址和密钥以及账号档案，不要分享这样的文件";
+const IMPORT_CONFIG_USAGE: &str = "用法: micept import-config --input <路径> [--dry-run]";
+
+/// 子命令处理函数，参数不包含子命令名称
+type Subcommand = fn(&[String]) -> Result<String, String>;
+
+/// 处理命令行子命令和启动参数
+///
+/// 返回 `Some(退出码)` 表示子命令已执行完毕、参数有误或参数已转发给运行中的实例，
+/// `None` 表示应正常启动界面，此时参数已作为本次运行的配置覆盖保存。
+/// 启动参数和 `MICEPT_*` 环境变量对子命令同样生效，例如 `micept export-config --config <路径> --output <路径>`。
+pub fn run(args: &[String]) -> Option<i32> {
+    if !args.is_empty() {
+        attach_console();
+    }
+
+    let position = launch::first_positional(args);
+    let subcommand: Option<Subcommand> = match position.map(|index| args[index].as_str()) {
+        Some("export-matches") => Some(export_matches),
+        Some("export-config") => Some(export_config),
+        Some("import-config") => Some(import_config),
+        Some(STATUS_COMMAND) => Some(status),
+        Some(RECONNECT_COMMAND) => Some(reconnect),
+        _ => None,
+    };
+
+    if let (Some(subcommand), Some(index)) = (subcommand, position) {
+        // 子命令前后的 `--config`、`--portable` 决定读写哪个配置文件
+        let (location, subcommand_args) = launch::split_location_args(&args[index + 1..]);
+        let launch_args = [&args[..index], &location[..]].concat();
+        let result = init_launch_options(&launch_args).and_then(|_| subcommand(&subcommand_args));
+        return Some(report(result));
+    }
+    if let Err(e) = init_launch_options(args) {
+        return Some(report(Err(e)));
+    }
+    instance::forward_if_running(args).map(report)
+}
+
+/// 附加到启动本程序的命令行窗口，让子命令和无界面模式的输出可见
+///
+/// Windows 下程序使用 GUI 子系统，默认没有控制台。标准输出已重定向或父进程没有控制台时不做任何事。
+#[cfg(target_os = "windows")]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // SAFETY: 只附加父进程的控制台，失败时返回 0，不影响后续运行
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_console() {}
+
+/// 解析启动参数和环境变量并设置为本次运行的配置覆盖
+fn init_launch_options(args: &[String]) -> Result<(), String> {
+    let options = LaunchOptions::parse(args.iter().cloned(), std::env::vars())?;
+    launch::init(options);
+    Ok(())
+}
+
+/// 输出执行结果并转换为退出码
+fn report(result: Result<String, String>) -> i32 {
+    match result {
+        Ok(message) => {
+            println!("{}", message);
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// 导出对局记录子命令
+fn export_matches(args: &[String]) -> Result<String, String> {
+    let mut output = None;
+    let mut format = None;
+    let mut limit = DEFAULT_MATCH_LIMIT;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--output" | "-o" => output = iter.next().cloned(),
+            "--format" | "-f" => format = iter.next().cloned(),
+            "--limit" | "-n" => {
+                limit = iter
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .ok_or_else(|| format!("无效的对局数量\n{}", EXPORT_USAGE))?;
+            }
+            other => return Err(format!("未知参数: {}\n{}", other, EXPORT_USAGE)),
+        }
+    }
+
+    let output = PathBuf::from(output.ok_or(EXPORT_USAGE)?);
+    let format = ExportFormat::parse(format.as_deref(), &output)?;
+
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| format!("创建运行时失败: {}", e))?;
+    runtime.block_on(async {
+        let auth = lol::get_lcu_auth().await?;
+        let count = export::export_match_history(&auth.port, &auth.token, &output, format, limit).await?;
+        Ok(format!("已导出{}局对局记录到: {}", count, output.display()))
+    })
+}
+
+/// 导出配置包子命令
+fn export_config(args: &[String]) -> Result<String, String> {
+    let mut output = None;
+    let mut include_config = true;
+    let mut include_preset = false;
+    let mut include_private = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--output" | "-o" => output = iter.next().cloned(),
+            "--preset" => include_preset = true,
+            "--preset-only" => {
+                include_config = false;
+                include_preset = true;
+            }
+            "--include-private" => include_private = true,
+            other => return Err(format!("未知参数: {}\n{}", other, EXPORT_CONFIG_USAGE)),
+        }
+    }
+
+    let output = PathBuf::from(output.ok_or(EXPORT_CONFIG_USAGE)?);
+    let bundle = bundle::create_bundle(&AppConfig::load(), include_config, include_preset, include_private)?;
+    bundle::write_bundle(&output, &bundle)?;
+    Ok(format!("已导出配置到: {}", output.display()))
+}
+
+/// 导入配置包子命令，先列出变化，`--dry-run` 时不写入
+fn import_config(args: &[String]) -> Result<String, String> {
+    let mut input = None;
+    let mut dry_run = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--input" | "-i" => input = iter.next().cloned(),
+            "--dry-run" => dry_run = true,
+            other => return Err(format!("未知参数: {}\n{}", other, IMPORT_CONFIG_USAGE)),
+        }
+    }
+
+    let input = PathBuf::from(input.ok_or(IMPORT_CONFIG_USAGE)?);
+    let bundle = bundle::read_bundle(&input)?;
+    let preview = bundle::preview_import(&bundle, &AppConfig::load())?;
+
+    for change in &preview.changes {
+        println!("{}: {} -> {}", change.path, change.before, change.after);
+    }
+    for field in &preview.rejected {
+        println!("已忽略无效字段: {}", field);
+    }
+
+    if preview.changes.is_empty() {
+        return Ok("配置没有变化".to_string());
+    }
+    if dry_run {
+        return Ok(format!("共{}项变化，未写入（--dry-run）", preview.changes.len()));
+    }
+    preview.config.save().map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(format!("已导入{}项配置变化", preview.changes.len()))
+}
+
+/// 输出运行中实例的状态
+fn status(args: &[String]) -> Result<String, String> {
+    send_to_running(STATUS_COMMAND, args)
+}
+
+/// 让运行中的实例立即重新连接英雄联盟客户端
+fn reconnect(args: &[String]) -> Result<String, String> {
+    send_to_running(RECONNECT_COMMAND, args)
+}
+
+/// 通过单实例转发把子命令交给运行中的实例
+fn send_to_running(command: &str, args: &[String]) -> Result<String, String> {
+    if let Some(arg) = args.first() {
+        return Err(format!("未知参数: {}\n用法: micept {}", arg, command));
+    }
+    instance::forward_if_running(&[command.to_string()]).unwrap_or_else(|| Err("micept 未在运行".to_string()))
+}
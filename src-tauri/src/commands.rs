@@ -1,45 +1,169 @@
-use crate::core::AppState;
+use crate::core::{background, config_watcher, diagnostics, events, AppState};
+use crate::ui::tray;
+use crate::utils::config::{AccountProfile, ChampionLists, ConfigUpdate, RunePage};
+use crate::utils::logging;
+use crate::utils::bundle::{self, ImportPreview};
+use crate::utils::export::{self, ExportFormat, DEFAULT_MATCH_LIMIT};
+use std::path::PathBuf;
 use tauri::State;
+use tracing::info;
 
 /// 获取当前应用状态
 #[tauri::command]
 pub fn get_app_state(state: State<AppState>) -> serde_json::Value {
-    let mouse_through = *state.mouse_through.lock().unwrap();
-    let auto_accept = *state.auto_accept.lock().unwrap();
-    let auto_hide = *state.auto_hide.lock().unwrap();
-    let gameflow_phase = state.gameflow_phase.lock().unwrap().clone();
-    let lcu_auth = state.lcu_auth.lock().unwrap().clone();
-    let summoner_info = state.summoner_info.lock().unwrap().clone();
+    app_state_snapshot(&state)
+}
+
+/// 当前应用状态的快照，供前端命令和本地API共用
+pub fn app_state_snapshot(state: &AppState) -> serde_json::Value {
+    let background = state.background.lock().unwrap().health.clone();
     
-    serde_json::json!({
-        "mouse_through": mouse_through,
-        "auto_accept": auto_accept,
-        "auto_hide": auto_hide,
-        "gameflow_phase": gameflow_phase,
-        "lcu_connected": lcu_auth.is_some(),
-        "summoner_info": summoner_info
+    state.read(|snapshot| {
+        let config = &snapshot.config;
+        serde_json::json!({
+            "mouse_through": config.mouse_through,
+            "auto_accept": config.auto_accept(),
+            "auto_hide": config.auto_hide,
+            "gameflow_phase": snapshot.gameflow_phase,
+            "lcu_connected": snapshot.lcu_auth.is_some(),
+            "summoner_info": snapshot.summoner_info,
+            "player_profile": snapshot.player_profile,
+            "session_summary": snapshot.session.summary(),
+            "active_profile": config.profiles.active_profile().name,
+            "background": background
+        })
     })
 }
 
 /// 设置自动接受状态
 #[tauri::command]
 pub fn set_auto_accept(state: State<AppState>, enabled: bool) -> Result<String, String> {
-    *state.auto_accept.lock().unwrap() = enabled;
-    // 更新配置文件
-    state.config.lock().unwrap().update_auto_accept(enabled);
+    tray::set_auto_accept(&state, enabled);
     Ok(format!("自动接受已{}", if enabled { "开启" } else { "关闭" }))
 }
 
+/// 获取当前配置档案，包含选用/禁用英雄和符文页
+#[tauri::command]
+pub fn get_active_profile(state: State<AppState>) -> AccountProfile {
+    state.read(|snapshot| snapshot.config.profiles.active_profile().clone())
+}
+
+/// 设置当前档案的选用和禁用英雄，按优先级排列的英雄ID
+#[tauri::command]
+pub fn set_champion_lists(state: State<AppState>, pick: Vec<u32>, ban: Vec<u32>) -> Result<String, String> {
+    state.update_config(ConfigUpdate::ChampionLists(ChampionLists { pick, ban }));
+    Ok("英雄列表已保存".to_string())
+}
+
+/// 设置当前档案的符文页
+#[tauri::command]
+pub fn set_rune_pages(state: State<AppState>, pages: Vec<RunePage>) -> Result<String, String> {
+    let count = pages.len();
+    state.update_config(ConfigUpdate::RunePages(pages));
+    Ok(format!("已保存{}个符文页", count))
+}
+
+/// 立即重新连接英雄联盟客户端，重启后台任务
+#[tauri::command]
+pub fn reconnect_lcu(state: State<AppState>) -> Result<String, String> {
+    background::restart(state.inner().clone())?;
+    Ok("正在重新连接".to_string())
+}
+
+/// 调整日志级别并保存到配置
+#[tauri::command]
+pub fn set_log_level(state: State<AppState>, level: String) -> Result<String, String> {
+    let level = level.to_lowercase();
+    logging::set_level(&level)?;
+    state.update_config(ConfigUpdate::LogLevel(level.clone()));
+    Ok(format!("日志级别已设置为: {}", level))
+}
+
 /// 保存窗口位置
 #[tauri::command]
 pub fn save_window_position(state: State<AppState>, x: i32, y: i32) -> Result<String, String> {
-    state.config.lock().unwrap().update_window_position(x, y);
+    state.update_config(ConfigUpdate::WindowPosition { x, y });
     Ok("窗口位置已保存".to_string())
 }
 
 /// 保存窗口可见性
 #[tauri::command]
 pub fn save_window_visible(state: State<AppState>, visible: bool) -> Result<String, String> {
-    state.config.lock().unwrap().update_window_visible(visible);
+    state.update_config(ConfigUpdate::WindowVisible(visible));
     Ok(format!("窗口可见性已保存: {}", visible))
-}
\ No newline at end of file
+}
+
+/// 导出对局记录到指定路径
+#[tauri::command]
+pub async fn export_match_history(
+    state: State<'_, AppState>,
+    path: String,
+    format: Option<String>,
+    limit: Option<usize>,
+) -> Result<String, String> {
+    let auth = state
+        .read(|snapshot| snapshot.lcu_auth.clone())
+        .ok_or("未连接到英雄联盟客户端")?;
+    let path = PathBuf::from(path);
+    let format = ExportFormat::parse(format.as_deref(), &path)?;
+    let count = export::export_match_history(
+        &auth.port,
+        &auth.token,
+        &path,
+        format,
+        limit.unwrap_or(DEFAULT_MATCH_LIMIT),
+    )
+    .await?;
+    Ok(format!("已导出{}局对局记录", count))
+}
+
+/// 导出配置包，可选择包含完整配置和英雄/符文预设
+///
+/// 默认不包含密钥和账号档案，`include_private` 为 true 时一并导出。
+#[tauri::command]
+pub fn export_config(
+    state: State<AppState>,
+    path: String,
+    include_config: Option<bool>,
+    include_preset: Option<bool>,
+    include_private: Option<bool>,
+) -> Result<String, String> {
+    let config = state.read(|snapshot| snapshot.config.clone());
+    let bundle = bundle::create_bundle(
+        &config,
+        include_config.unwrap_or(true),
+        include_preset.unwrap_or(false),
+        include_private.unwrap_or(false),
+    )?;
+    bundle::write_bundle(&PathBuf::from(path), &bundle)?;
+    Ok("配置已导出".to_string())
+}
+
+/// 导出诊断包到指定路径，包含隐藏敏感信息后的日志、配置和连接检查结果
+#[tauri::command]
+pub async fn export_diagnostics(state: State<'_, AppState>, path: String) -> Result<String, String> {
+    diagnostics::export(&state, &PathBuf::from(path)).await?;
+    Ok("诊断包已导出".to_string())
+}
+
+/// 预览导入配置包后的变化
+#[tauri::command]
+pub fn preview_config_import(state: State<AppState>, path: String) -> Result<ImportPreview, String> {
+    let bundle = bundle::read_bundle(&PathBuf::from(path))?;
+    let current = state.read(|snapshot| snapshot.config.clone());
+    bundle::preview_import(&bundle, &current)
+}
+
+/// 导入配置包并立即生效
+#[tauri::command]
+pub fn import_config(app: tauri::AppHandle, state: State<AppState>, path: String) -> Result<ImportPreview, String> {
+    let preview = preview_config_import(state.clone(), path)?;
+    let mut config = preview.config.clone();
+    config.apply_overrides();
+    let autostart_before = state.read(|snapshot| snapshot.config.autostart);
+    state.update_config(ConfigUpdate::Replace(Box::new(config)));
+    config_watcher::sync_with_config(&app, &state, autostart_before);
+    events::emit(&app, &state, "config-reloaded", ());
+    info!("已导入配置包，共{}项变化", preview.changes.len());
+    Ok(preview)
+}
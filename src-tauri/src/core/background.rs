@@ -1,70 +1,171 @@
-use crate::core::AppState;
-use crate::lol;
-use tauri::{Manager, Emitter};
+use crate::core::{
+    events,
+    frontend::Frontend,
+    profiles,
+    session::{publish_summary, SessionAction},
+    state::StateAction,
+    AppState,
+};
+use crate::integrations::discord::{Activity, DiscordPresence};
+use crate::integrations::webhook::{WebhookDispatcher, WebhookEvent};
+use crate::lol::{LcuApi, LiveLcu};
+use crate::ui::notification::{NotificationEvent, Notifier};
+use chrono::Local;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// 后台任务的运行状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    #[default]
+    NotStarted,
+    Running,
+    Stopped,
+}
+
+/// 后台任务的健康状况，通过 `get_app_state` 提供给前端
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BackgroundHealth {
+    pub status: TaskStatus,
+    /// 手动重新连接的次数
+    pub restarts: u32,
+    pub started_at: Option<i64>,
+    /// 最近一次开始检查的时间，长时间不更新说明任务卡住
+    pub last_check_at: Option<i64>,
+}
+
+/// 后台任务句柄，保存在 `AppState` 中
+#[derive(Default)]
+pub struct BackgroundHandle {
+    task: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// 启动时使用的界面接口，重新连接时沿用
+    frontend: Option<Frontend>,
+    /// Discord 状态同步任务，重新连接时沿用，退出时随 `shutdown` 停止
+    presence: Option<Arc<DiscordPresence>>,
+    pub health: BackgroundHealth,
+}
+
+/// 在 Tauri 的异步运行时上启动后台任务，已有任务时先停止旧任务
+pub fn start(frontend: Frontend, state: AppState) {
+    let mut background = state.background.lock().unwrap();
+    if let Some(task) = background.task.take() {
+        task.abort();
+    }
+    background.health.status = TaskStatus::Running;
+    background.health.started_at = Some(Local::now().timestamp());
+
+    background.frontend = Some(frontend.clone());
+    let task = state.shutdown.track(background_task(frontend, Arc::new(LiveLcu), state.clone()));
+    background.task = Some(tauri::async_runtime::spawn(task));
+}
+
+/// 立即重新连接：停止后台任务，清除缓存的认证信息后重新启动
+pub fn restart(state: AppState) -> Result<(), String> {
+    info!("lcu reconnect requested");
+    let frontend = {
+        let mut background = state.background.lock().unwrap();
+        let frontend = background.frontend.clone().ok_or("后台任务尚未启动")?;
+        if let Some(task) = background.task.take() {
+            task.abort();
+        }
+        background.health.restarts += 1;
+        frontend
+    };
+
+    state.dispatch(StateAction::LcuDisconnected);
+    events::emit(frontend.events.as_ref(), &state, "lcu-status-changed", false);
+    start(frontend, state);
+    Ok(())
+}
 
 /// 后台状态管理任务
-pub async fn background_task(app_handle: tauri::AppHandle, state: AppState) {
-    eprintln!("后台任务已启动");
+async fn background_task(frontend: Frontend, lcu: Arc<dyn LcuApi>, state: AppState) {
+    info!("background task started");
     
-    // 使用自适应间隔，根据LCU连接状态调整检查频率
+    // 使用自适应间隔，根据LCU连接状态调整检查频率，启动后立即进行第一次检查
     let base_interval = Duration::from_secs(3);
-    let mut current_interval = base_interval;
+    let mut current_interval = Duration::ZERO;
     let mut consecutive_failures = 0;
+    // 召唤师信息与玩家资料只在连接后和每局结束后刷新
+    let mut refresh_profile = true;
+    let sink = frontend.events.as_ref();
+    let notifier = frontend.notifier.as_ref();
+    let webhooks = WebhookDispatcher::spawn(&state.shutdown);
+    let mut was_pick_turn = false;
+    let presence = state
+        .background
+        .lock()
+        .unwrap()
+        .presence
+        .get_or_insert_with(|| Arc::new(DiscordPresence::spawn(state.clone())))
+        .clone();
+    // Discord 状态需要的阶段开始时间与本地玩家英雄
+    let mut phase_started_at = Local::now().timestamp();
+    let mut local_champion: Option<u32> = None;
+    let mut champion_names: HashMap<u32, String> = HashMap::new();
     
     loop {
-        tokio::time::sleep(current_interval).await;
+        // 等待下一次检查，退出时立即停止
+        tokio::select! {
+            _ = tokio::time::sleep(current_interval) => {}
+            _ = state.shutdown.cancelled() => {
+                info!("background task stopped");
+                break;
+            }
+        }
+        state.background.lock().unwrap().health.last_check_at = Some(Local::now().timestamp());
         
-        // 检查是否应该停止
-        if !*state.is_running.lock().unwrap() {
-            println!("后台任务停止");
-            break;
+        // 跨天时重置会话统计
+        if state.dispatch(StateAction::Session(SessionAction::RollOver)) {
+            info!("session stats reset for new day");
+            publish_summary(sink, &state);
         }
         
-        eprintln!("后台任务运行中，检查LCU连接状态...");
+        debug!("checking lcu connection");
         
         // 首先检查是否已有缓存的LCU认证信息
-        let cached_auth = {
-            let lcu_guard = state.lcu_auth.lock().unwrap();
-            lcu_guard.clone()
-        };
+        let cached_auth = state.read(|snapshot| snapshot.lcu_auth.clone());
         
         let auth = match cached_auth {
             Some(cached) => {
                 // 验证缓存的认证信息是否仍然有效
-                println!("使用缓存的LCU认证信息进行验证...");
-                match lol::validate_lcu_connection(cached.port.clone(), cached.token.clone()).await {
+                debug!(source = "cache", "validating lcu auth");
+                match lcu.validate(&cached).await {
                     Ok(_) => {
-                        println!("缓存的LCU认证信息仍然有效");
+                        debug!(source = "cache", "lcu auth valid");
                         consecutive_failures = 0;
                         current_interval = base_interval;
                         cached
                     }
                     Err(_) => {
-                        println!("缓存的LCU认证信息已失效，重新获取...");
+                        info!(source = "cache", "lcu auth expired, rediscovering");
                         // 缓存失效，重新获取
-                        match lol::get_lcu_auth().await {
+                        match lcu.discover().await {
                             Ok(new_auth) => {
-                                println!("成功获取新的LCU认证信息: port={}, token={}...", new_auth.port, &new_auth.token[..8.min(new_auth.token.len())]);
+                                info!(port = %new_auth.port, source = "rediscover", "lcu connected");
                                 consecutive_failures = 0;
                                 current_interval = base_interval;
                                 
                                 // 更新缓存
-                                *state.lcu_auth.lock().unwrap() = Some(new_auth.clone());
-                                let _ = app_handle.emit("lcu-status-changed", true);
+                                state.dispatch(StateAction::LcuConnected(new_auth.clone()));
+                                events::emit(sink, &state, "lcu-status-changed", true);
+                                refresh_profile = true;
                                 
                                 new_auth
                             }
                             Err(e) => {
-                                println!("重新获取LCU认证信息失败: {}", e);
                                 consecutive_failures += 1;
+                                warn!(error = %e, failures = consecutive_failures, source = "rediscover", "lcu discovery failed");
                                 current_interval = base_interval * (1 + consecutive_failures.min(5));
                                 
                                 // 清理缓存和状态
-                                *state.lcu_auth.lock().unwrap() = None;
-                                *state.summoner_info.lock().unwrap() = None;
-                                *state.gameflow_phase.lock().unwrap() = "None".to_string();
-                                let _ = app_handle.emit("lcu-status-changed", false);
+                                state.dispatch(StateAction::LcuDisconnected);
+                                events::emit(sink, &state, "lcu-status-changed", false);
+                                presence.update(None);
                                 continue;
                             }
                         }
@@ -73,22 +174,23 @@ pub async fn background_task(app_handle: tauri::AppHandle, state: AppState) {
             }
             None => {
                 // 没有缓存，首次获取
-                println!("首次获取LCU认证信息...");
-                match lol::get_lcu_auth().await {
+                debug!(source = "discover", "discovering lcu auth");
+                match lcu.discover().await {
                     Ok(new_auth) => {
-                        println!("成功获取LCU认证信息: port={}, token={}...", new_auth.port, &new_auth.token[..8.min(new_auth.token.len())]);
+                        info!(port = %new_auth.port, source = "discover", "lcu connected");
                         consecutive_failures = 0;
                         current_interval = base_interval;
                         
                         // 缓存认证信息
-                        *state.lcu_auth.lock().unwrap() = Some(new_auth.clone());
-                        let _ = app_handle.emit("lcu-status-changed", true);
+                        state.dispatch(StateAction::LcuConnected(new_auth.clone()));
+                        events::emit(sink, &state, "lcu-status-changed", true);
+                        refresh_profile = true;
                         
                         new_auth
                     }
                     Err(e) => {
-                        println!("获取LCU认证信息失败: {}", e);
                         consecutive_failures += 1;
+                        warn!(error = %e, failures = consecutive_failures, source = "discover", "lcu discovery failed");
                         current_interval = base_interval * (1 + consecutive_failures.min(5));
                         continue;
                     }
@@ -96,45 +198,126 @@ pub async fn background_task(app_handle: tauri::AppHandle, state: AppState) {
             }
         };
         
-        // 获取召唤师信息 - 每次都尝试获取以确保信息是最新的
-        println!("获取召唤师信息...");
-        match lol::get_summoner_info(auth.port.clone(), auth.token.clone()).await {
-            Ok(summoner) => {
-                println!("成功获取召唤师信息: {}", summoner.display_name);
-                let mut summoner_guard = state.summoner_info.lock().unwrap();
-                let summoner_changed = match &*summoner_guard {
-                    Some(existing) => existing.display_name != summoner.display_name || existing.summoner_level != summoner.summoner_level,
-                    None => true,
-                };
-                
-                if summoner_changed {
-                    *summoner_guard = Some(summoner.clone());
-                    drop(summoner_guard);
-                    let _ = app_handle.emit("summoner-info-updated", &summoner);
-                    println!("召唤师信息已更新并发送事件");
-                } else {
-                    println!("召唤师信息无变化");
+        // 首次连接时采样排位数据，作为会话统计的基准
+        let has_sample = state.read(|snapshot| snapshot.session.has_sample());
+        if !has_sample {
+            match lcu.ranked_stats(&auth).await {
+                Ok(stats) => {
+                    info!("ranked stats sampled as session baseline");
+                    state.dispatch(StateAction::Session(SessionAction::Sample(stats)));
+                    publish_summary(sink, &state);
+                }
+                Err(e) => {
+                    warn!(error = %e, "failed to fetch ranked stats");
                 }
             }
-            Err(e) => {
-                println!("获取召唤师信息失败: {}", e);
-                *state.summoner_info.lock().unwrap() = None;
+        }
+        
+        // 连接后和每局结束后并发刷新召唤师信息与玩家资料
+        if refresh_profile {
+            debug!("refreshing summoner and profile");
+            let (summoner_result, profile_result) = tokio::join!(lcu.summoner_info(&auth), lcu.player_profile(&auth));
+            
+            match summoner_result {
+                Ok(summoner) => {
+                    info!(summoner = %summoner.display_name, level = summoner.summoner_level, "summoner info updated");
+                    state.dispatch(StateAction::SummonerInfo(Some(summoner.clone())));
+                    events::emit(sink, &state, "summoner-info-updated", &summoner);
+                    refresh_profile = false;
+                    
+                    // 只在本次运行中登录的账号变化时切换配置档案，重启后台任务不会覆盖手动选择的档案
+                    if !summoner.puuid.is_empty()
+                        && state.dispatch(StateAction::AccountDetected(summoner.puuid.clone()))
+                    {
+                        info!(summoner = %summoner.display_name, "account changed, switching profile");
+                        profiles::switch_profile(sink, &state, Some(summoner.puuid.clone()), &summoner.display_name);
+                    }
+                }
+                Err(e) => {
+                    warn!(error = %e, "failed to fetch summoner info");
+                    state.dispatch(StateAction::SummonerInfo(None));
+                }
+            }
+            
+            match profile_result {
+                Ok(profile) => {
+                    state.dispatch(StateAction::PlayerProfile(Some(profile.clone())));
+                    events::emit(sink, &state, "player-profile-updated", &profile);
+                    debug!("player profile updated");
+                }
+                Err(e) => {
+                    warn!(error = %e, "failed to fetch player profile");
+                }
             }
         }
         
         // 获取游戏流程状态
-        match lol::get_gameflow_phase(auth.port.clone(), auth.token.clone()).await {
+        match lcu.gameflow_session(&auth).await {
             Ok(session) => {
-                let old_phase = {
-                    let phase_guard = state.gameflow_phase.lock().unwrap();
-                    phase_guard.clone()
-                };
+                let old_phase = state.read(|snapshot| snapshot.gameflow_phase.clone());
                 
                 // 只有状态真正改变时才更新
                 if old_phase != session.phase {
-                    println!("游戏状态变化: {} -> {}", old_phase, session.phase);
-                    *state.gameflow_phase.lock().unwrap() = session.phase.clone();
-                    let _ = app_handle.emit("gameflow-changed", &session.phase);
+                    info!(from = %old_phase, to = %session.phase, queue = %session.queue_name, "gameflow phase changed");
+                    state.dispatch(StateAction::GameflowPhase(session.phase.clone()));
+                    events::emit(sink, &state, "gameflow-changed", &session.phase);
+                    
+                    // 英雄选择会延续到游戏中，其它阶段清除已选英雄
+                    phase_started_at = Local::now().timestamp();
+                    if !matches!(session.phase.as_str(), "InProgress" | "Reconnect") {
+                        local_champion = None;
+                    }
+                    
+                    // 阶段时间已随阶段切换计入会话统计，游戏结束后重新采样排位数据
+                    if session.phase == "EndOfGame" {
+                        let stats = match lcu.ranked_stats(&auth).await {
+                            Ok(stats) => Some(stats),
+                            Err(e) => {
+                                warn!(error = %e, "failed to fetch ranked stats after game");
+                                None
+                            }
+                        };
+                        state.dispatch(StateAction::Session(SessionAction::GameEnd(stats)));
+                        refresh_profile = true;
+                        
+                        // 通知 Webhook 对局结果
+                        let data = match lcu.end_of_game_stats(&auth).await {
+                            Ok(eog) => serde_json::json!({
+                                "summoner": summoner_name(&state),
+                                "win": eog.is_win(),
+                                "game_id": eog.game_id,
+                                "game_length": eog.game_length,
+                                "queue_type": eog.queue_type,
+                                "champion_id": eog.local_player.champion_id,
+                            }),
+                            Err(e) => {
+                                warn!(error = %e, "failed to fetch end of game stats");
+                                serde_json::json!({ "summoner": summoner_name(&state), "win": null })
+                            }
+                        };
+                        send_webhook(&webhooks, &state, WebhookEvent::GameEnd, data);
+                    }
+                    if session.phase == "InProgress" {
+                        let data = serde_json::json!({ "summoner": summoner_name(&state) });
+                        send_webhook(&webhooks, &state, WebhookEvent::GameStart, data);
+                    }
+                    publish_summary(sink, &state);
+                    
+                    // 发送桌面通知
+                    match session.phase.as_str() {
+                        "ReadyCheck" => {
+                            // 窗口隐藏或未开启自动接受时，准备检查很容易错过
+                            let window_visible = frontend.window.is_visible();
+                            let auto_accept_enabled = state.read(|snapshot| snapshot.config.auto_accept());
+                            debug!(window_visible, auto_accept = auto_accept_enabled, "ready check notification check");
+                            if !window_visible || !auto_accept_enabled {
+                                send_notification(notifier, &state, NotificationEvent::ReadyCheck);
+                            }
+                        }
+                        "ChampSelect" => send_notification(notifier, &state, NotificationEvent::ChampSelect),
+                        "InProgress" => send_notification(notifier, &state, NotificationEvent::GameStart),
+                        _ => {}
+                    }
                     
                     // 根据游戏状态调整检查频率
                     match session.phase.as_str() {
@@ -147,68 +330,216 @@ pub async fn background_task(app_handle: tauri::AppHandle, state: AppState) {
                             current_interval = Duration::from_secs(10);
                             
                             // 检查自动隐藏功能是否开启
-                            let auto_hide_enabled = *state.auto_hide.lock().unwrap();
-                            println!("游戏开始 - 自动隐藏状态: {}", auto_hide_enabled);
+                            let auto_hide_enabled = state.read(|snapshot| snapshot.config.auto_hide);
+                            debug!(auto_hide = auto_hide_enabled, "game started");
                             if auto_hide_enabled {
-                                if let Some(window) = app_handle.get_webview_window("main") {
-                                    match window.hide() {
-                                        Ok(_) => println!("✓ 自动隐藏功能已开启，窗口已成功隐藏"),
-                                        Err(e) => println!("✗ 隐藏窗口失败: {:?}", e),
-                                    }
-                                } else {
-                                    println!("✗ 无法获取主窗口");
+                                match frontend.window.set_visible(false) {
+                                    Ok(_) => info!(action = "hide", "auto hide applied"),
+                                    Err(e) => warn!(action = "hide", error = %e, "auto hide failed"),
                                 }
-                            } else {
-                                println!("自动隐藏功能已关闭，窗口保持显示");
                             }
                         }
                         _ => {
                             current_interval = base_interval;
                             // 从游戏中退出时，如果自动隐藏功能开启，则显示窗口
                             if old_phase == "InProgress" {
-                                let auto_hide_enabled = *state.auto_hide.lock().unwrap();
-                                println!("游戏结束 - 自动隐藏状态: {}", auto_hide_enabled);
+                                let auto_hide_enabled = state.read(|snapshot| snapshot.config.auto_hide);
+                                debug!(auto_hide = auto_hide_enabled, "game ended");
                                 if auto_hide_enabled {
-                                    if let Some(window) = app_handle.get_webview_window("main") {
-                                        match window.show() {
-                                            Ok(_) => println!("✓ 游戏结束，窗口已成功显示"),
-                                            Err(e) => println!("✗ 显示窗口失败: {:?}", e),
-                                        }
-                                    } else {
-                                        println!("✗ 无法获取主窗口");
+                                    match frontend.window.set_visible(true) {
+                                        Ok(_) => info!(action = "show", "auto hide applied"),
+                                        Err(e) => warn!(action = "show", error = %e, "auto hide failed"),
                                     }
-                                } else {
-                                    println!("自动隐藏功能已关闭，窗口状态不变");
                                 }
                             }
                         }
                     }
                 }
                 
+                // 英雄选择阶段检测是否轮到自己选择英雄
+                if session.phase == "ChampSelect" {
+                    match lcu.champ_select_session(&auth).await {
+                        Ok(champ_select) => {
+                            let is_pick_turn = champ_select.is_local_pick_turn();
+                            if is_pick_turn && !was_pick_turn {
+                                send_notification(notifier, &state, NotificationEvent::PickTurn);
+                            }
+                            was_pick_turn = is_pick_turn;
+                            
+                            local_champion = champ_select.local_champion_id();
+                            if local_champion.is_some() && champion_names.is_empty() {
+                                champion_names = lcu.champion_names(&auth).await;
+                            }
+                        }
+                        Err(e) => {
+                            warn!(phase = %session.phase, error = %e, "failed to fetch champ select session");
+                        }
+                    }
+                } else {
+                    was_pick_turn = false;
+                }
+                
+                // 同步 Discord 状态，内容未变化时不会重复发送
+                let champion = local_champion.and_then(|id| champion_names.get(&id)).map(String::as_str);
+                presence.update(Activity::for_phase(&session.phase, &session.queue_name, champion, phase_started_at));
+                
                 // 自动接受匹配
                 if session.phase == "ReadyCheck" {
-                    let auto_accept_enabled = *state.auto_accept.lock().unwrap();
-                    println!("检测到ReadyCheck状态，自动接受开关: {}", auto_accept_enabled);
+                    let auto_accept_enabled = state.read(|snapshot| snapshot.config.auto_accept());
+                    debug!(auto_accept = auto_accept_enabled, "ready check detected");
                     
                     if auto_accept_enabled {
-                        println!("尝试自动接受匹配...");
-                        match lol::accept_match(auth.port, auth.token).await {
+                        match lcu.accept_match(&auth).await {
                             Ok(_) => {
-                                println!("匹配已自动接受");
-                                let _ = app_handle.emit("match-accepted", "匹配已自动接受");
+                                info!(outcome = "accepted", "ready check handled");
+                                events::emit(sink, &state, "match-accepted", "匹配已自动接受");
+                                let data = serde_json::json!({ "summoner": summoner_name(&state) });
+                                send_webhook(&webhooks, &state, WebhookEvent::ReadyCheckAccepted, data);
                             }
                             Err(e) => {
-                                eprintln!("自动接受匹配失败: {}", e);
+                                warn!(outcome = "failed", error = %e, "ready check handled");
                             }
                         }
                     } else {
-                        println!("自动接受功能已关闭，跳过自动接受");
+                        debug!(outcome = "skipped", "ready check handled");
                     }
                 }
             }
             Err(_) => {
-                *state.gameflow_phase.lock().unwrap() = "None".to_string();
+                state.dispatch(StateAction::GameflowPhase("None".to_string()));
+                presence.update(None);
             }
         }
     }
-}
\ No newline at end of file
+    
+    state.background.lock().unwrap().health.status = TaskStatus::Stopped;
+}
+
+/// 按当前配置发送桌面通知
+fn send_notification(notifier: &Notifier, state: &AppState, event: NotificationEvent) {
+    let config = state.read(|snapshot| snapshot.config.notifications.clone());
+    if notifier.notify(event, &config, Local::now().time()) {
+        info!(event = ?event, "notification sent");
+    }
+}
+
+/// 按当前配置将事件加入 Webhook 发送队列
+fn send_webhook(webhooks: &WebhookDispatcher, state: &AppState, event: WebhookEvent, data: serde_json::Value) {
+    let config = state.read(|snapshot| snapshot.config.webhooks.clone());
+    let queued = webhooks.dispatch(event, data, &config);
+    if queued > 0 {
+        debug!(event = event.name(), queued, "webhook queued");
+    }
+}
+
+/// 当前召唤师名称
+fn summoner_name(state: &AppState) -> Option<String> {
+    state.read(|snapshot| {
+        snapshot
+            .summoner_info
+            .as_ref()
+            .map(|summoner| summoner.display_name.clone())
+    })
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, FakeLcu, FakeWindow, Recorder};
+    use crate::utils::{config::ConfigUpdate, AppConfig};
+    use std::sync::atomic::Ordering;
+
+    const FULL_GAME: [&str; 7] = ["Lobby", "Matchmaking", "ReadyCheck", "ChampSelect", "InProgress", "EndOfGame", "Lobby"];
+
+    struct Run {
+        state: AppState,
+        lcu: Arc<FakeLcu>,
+        events: Recorder,
+        notifications: Recorder,
+        window: Arc<FakeWindow>,
+    }
+
+    /// 用替身运行后台任务，直到脚本中的阶段全部返回
+    async fn run(config: AppConfig, phases: &[&'static str]) -> Run {
+        run_with_state(AppState::with_config(config), phases).await
+    }
+
+    async fn run_with_state(state: AppState, phases: &[&'static str]) -> Run {
+        let lcu = Arc::new(FakeLcu::new(phases));
+        let events = Recorder::default();
+        let notifications = Recorder::default();
+        let window = Arc::new(FakeWindow::default());
+        window.visible.store(true, Ordering::SeqCst);
+        let frontend = test_support::frontend(&events, &notifications, window.clone());
+
+        let task = tokio::spawn(state.shutdown.track(background_task(frontend, lcu.clone(), state.clone())));
+        while !lcu.finished.load(Ordering::SeqCst) {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        assert!(state.shutdown.run(Duration::from_secs(1)).await);
+        task.await.unwrap();
+
+        Run {
+            state,
+            lcu,
+            events,
+            notifications,
+            window,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn full_game_drives_events_window_and_session() {
+        let config = AppConfig {
+            auto_hide: true,
+            ..AppConfig::default()
+        };
+        let run = run(config, &FULL_GAME).await;
+
+        let phases: Vec<String> = FULL_GAME.iter().map(|phase| format!("\"{}\"", phase)).collect();
+        assert_eq!(run.events.named("gameflow-changed"), phases);
+        assert_eq!(run.events.named("lcu-status-changed"), vec!["true"]);
+        assert_eq!(run.events.named("match-accepted").len(), 1);
+        assert_eq!(run.lcu.accepts.load(Ordering::SeqCst), 1);
+        assert_eq!(*run.window.calls.lock().unwrap(), vec![false, true]);
+
+        // 窗口可见且已自动接受时不提醒准备检查
+        assert!(!run.notifications.0.lock().unwrap().contains(&"notify:找到对局".to_string()));
+
+        assert_eq!(run.state.read(|snapshot| snapshot.phase_history.len()), FULL_GAME.len());
+        assert_eq!(run.state.read(|snapshot| snapshot.session.summary().games), 1);
+        assert_eq!(run.state.background.lock().unwrap().health.status, TaskStatus::Stopped);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ready_check_without_auto_accept_notifies() {
+        let mut config = AppConfig::default();
+        ConfigUpdate::AutoAccept(false).apply(&mut config);
+        let run = run(config, &["Lobby", "ReadyCheck", "Lobby"]).await;
+
+        assert_eq!(run.lcu.accepts.load(Ordering::SeqCst), 0);
+        assert!(run.events.named("match-accepted").is_empty());
+        assert!(run.notifications.0.lock().unwrap().contains(&"notify:找到对局".to_string()));
+        assert!(run.window.calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn first_connection_switches_to_account_profile() {
+        let run = run(AppConfig::default(), &["Lobby"]).await;
+        run.state.read(|snapshot| {
+            assert_eq!(snapshot.config.profiles.active.as_deref(), Some("fake-puuid"));
+            assert_eq!(snapshot.account_puuid.as_deref(), Some("fake-puuid"));
+        });
+        assert_eq!(run.events.named("profile-switched").len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn restart_keeps_manually_selected_profile() {
+        // 之前的后台任务已检测到该账号，之后用户在托盘中手动切回默认档案
+        let state = AppState::with_config(AppConfig::default());
+        state.dispatch(StateAction::AccountDetected("fake-puuid".to_string()));
+
+        let run = run_with_state(state, &["Lobby"]).await;
+        assert_eq!(run.state.read(|snapshot| snapshot.config.profiles.active.clone()), None);
+        assert!(run.events.named("profile-switched").is_empty());
+    }
+}
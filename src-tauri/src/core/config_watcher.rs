@@ -0,0 +1,124 @@
+use super::{events, frontend::Frontend, state::StateAction, AppState};
+use crate::ui::window::WindowController;
+use crate::utils::{autostart, logging, AppConfig};
+use notify::{RecursiveMode, Watcher};
+use std::path::Path;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// 文件变化后等待的时间，编辑器保存时通常会连续触发多个事件
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 监视配置文件，外部修改后重新加载
+///
+/// 监视的是配置目录而不是文件本身，因为保存时会用重命名替换文件。
+/// 内容与本程序最近一次写入相同的变化会被忽略。
+pub fn start(frontend: Frontend, state: AppState) {
+    let path = match AppConfig::config_path() {
+        Ok(path) => path,
+        Err(e) => {
+            warn!("配置文件监视启动失败: {}", e);
+            return;
+        }
+    };
+    let Some(directory) = path.parent().map(Path::to_path_buf) else {
+        return;
+    };
+
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let watched_path = path.clone();
+    let watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        if let Ok(event) = result {
+            if event.paths.iter().any(|changed| changed == &watched_path) {
+                let _ = sender.send(());
+            }
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            warn!("创建配置文件监视器失败: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(&directory, RecursiveMode::NonRecursive) {
+        warn!("监视配置目录失败: {}", e);
+        return;
+    }
+    debug!("正在监视配置文件: {:?}", path);
+
+    tauri::async_runtime::spawn(async move {
+        // 监视器随任务一起存活
+        let _watcher = watcher;
+
+        while receiver.recv().await.is_some() {
+            tokio::time::sleep(RELOAD_DEBOUNCE).await;
+            while receiver.try_recv().is_ok() {}
+
+            let content = match tokio::fs::read_to_string(&path).await {
+                Ok(content) => content,
+                // 文件可能正在被替换，等待下一次变化
+                Err(_) => continue,
+            };
+            if state.config_last_saved.lock().unwrap().as_deref() == Some(content.as_str()) {
+                continue;
+            }
+
+            match AppConfig::parse(&content) {
+                Ok((mut config, _, rejected)) => {
+                    config.apply_overrides();
+                    if !rejected.is_empty() {
+                        warn!("外部修改的配置字段无效，已使用默认值: {}", rejected.join(", "));
+                    }
+                    apply_reloaded_config(&frontend, &state, config);
+                    *state.config_last_saved.lock().unwrap() = Some(content);
+                }
+                Err(e) => {
+                    warn!("外部修改的配置无效，已忽略: {}", e);
+                    events::emit(frontend.events.as_ref(), &state, "config-reload-failed", &e);
+                }
+            }
+        }
+    });
+}
+
+/// 将重新加载的配置应用到状态和窗口
+fn apply_reloaded_config(frontend: &Frontend, state: &AppState, config: AppConfig) {
+    let autostart_before = state.read(|snapshot| snapshot.config.autostart);
+    state.dispatch(StateAction::ReloadConfig(Box::new(config)));
+    sync_with_config(frontend.window.as_ref(), state, autostart_before);
+    events::emit(frontend.events.as_ref(), state, "config-reloaded", ());
+    info!("已重新加载外部修改的配置");
+}
+
+/// 按当前配置同步窗口位置、鼠标穿透、日志级别和开机自启，托盘菜单由状态订阅任务更新
+///
+/// 开机自启只在与 `autostart_before`（替换配置前的值）不同时重新注册。
+pub fn sync_with_config(window: &dyn WindowController, state: &AppState, autostart_before: bool) {
+    let (mouse_through, position, log_level, autostart_enabled) = state.read(|snapshot| {
+        let config = &snapshot.config;
+        (
+            config.mouse_through,
+            config.window_position.clone(),
+            config.log_level.clone(),
+            config.autostart,
+        )
+    });
+
+    if let Err(e) = logging::set_level(&log_level) {
+        warn!("{}", e);
+    }
+    if autostart_enabled != autostart_before {
+        if let Err(e) = autostart::set_enabled(autostart_enabled) {
+            warn!("设置开机自启失败: {}", e);
+        }
+    }
+
+    if let Err(e) = window.set_position(position.x, position.y) {
+        warn!("设置窗口位置失败: {}", e);
+    }
+    if let Err(e) = window.set_mouse_through(mouse_through) {
+        warn!("Failed to set ignore cursor events: {:?}", e);
+    }
+}
@@ -0,0 +1,87 @@
+use super::{events, frontend::Frontend, AppState};
+use crate::utils::AppConfig;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// 收到修改后等待的时间，期间的后续修改合并为一次写入
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 启动配置写入任务
+///
+/// 修改已由 `AppState::update_config` 应用到内存，这里只负责把最新配置写入文件，
+/// 写入失败或配置来自更新版本而不能写入时，向前端发送 `config-save-failed` 事件。
+/// 退出时立即写入尚未保存的修改，不再等待合并。
+pub fn start(frontend: Frontend, state: AppState) {
+    let Some(mut receiver) = state.config_update_receiver.lock().unwrap().take() else {
+        info!("配置写入任务已在运行");
+        return;
+    };
+
+    let shutdown = state.shutdown.clone();
+    tauri::async_runtime::spawn(shutdown.track(async move {
+        loop {
+            let update = tokio::select! {
+                update = receiver.recv() => update,
+                _ = state.shutdown.cancelled() => None,
+            };
+            let Some(update) = update else {
+                break;
+            };
+            let mut pending = vec![update];
+
+            // 合并短时间内的连续修改，例如拖动窗口时的位置更新
+            let debounce = tokio::time::sleep(SAVE_DEBOUNCE);
+            tokio::pin!(debounce);
+            loop {
+                tokio::select! {
+                    _ = &mut debounce => break,
+                    _ = state.shutdown.cancelled() => break,
+                    next = receiver.recv() => match next {
+                        Some(update) => pending.push(update),
+                        None => break,
+                    },
+                }
+            }
+
+            save(&frontend, &state, pending.len()).await;
+        }
+
+        // 收到退出信号时队列中可能还有未处理的修改
+        let mut remaining = 0;
+        while receiver.try_recv().is_ok() {
+            remaining += 1;
+        }
+        if remaining > 0 {
+            save(&frontend, &state, remaining).await;
+        }
+        info!("配置写入任务已停止");
+    }));
+}
+
+/// 将内存中的配置写入文件，`count` 为本次合并的修改数量
+async fn save(frontend: &Frontend, state: &AppState, count: usize) {
+    let content = state.read(|snapshot| {
+        let config = &snapshot.config;
+        config
+            .check_writable()
+            .and_then(|_| config.to_file_content().map_err(|e| e.to_string()))
+    });
+    let result = match content {
+        Ok(content) => {
+            // 先记录内容再写入，文件监视器据此识别自己的保存
+            *state.config_last_saved.lock().unwrap() = Some(content.clone());
+            tokio::task::spawn_blocking(move || AppConfig::write_file(&content).map_err(|e| e.to_string()))
+                .await
+                .unwrap_or_else(|e| Err(e.to_string()))
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(_) => debug!("已合并保存{}项配置修改", count),
+        Err(e) => {
+            warn!("保存配置失败: {}", e);
+            events::emit(frontend.events.as_ref(), state, "config-save-failed", &e);
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use super::AppState;
+use serde::Serialize;
+use tauri::Emitter;
+
+/// 事件总线容量，订阅者落后过多时会丢失最早的事件
+pub const EVENT_BUS_CAPACITY: usize = 64;
+
+/// 后台推送的事件，同时发送给前端和本地订阅者
+#[derive(Clone, Debug, Serialize)]
+pub struct AppEvent {
+    pub name: &'static str,
+    pub payload: serde_json::Value,
+}
+
+/// 事件输出，界面模式下发送给前端，无界面模式下输出到标准输出
+pub trait EventSink: Send + Sync {
+    fn send(&self, name: &'static str, payload: &serde_json::Value);
+}
+
+impl EventSink for tauri::AppHandle {
+    fn send(&self, name: &'static str, payload: &serde_json::Value) {
+        let _ = Emitter::emit(self, name, payload);
+    }
+}
+
+/// 向前端发送事件，并广播给本地API等订阅者
+pub fn emit<S: Serialize>(sink: &dyn EventSink, state: &AppState, name: &'static str, payload: S) {
+    let payload = serde_json::to_value(payload).unwrap_or(serde_json::Value::Null);
+    sink.send(name, &payload);
+    // 没有订阅者时发送会失败，可以忽略
+    let _ = state.events.send(AppEvent { name, payload });
+}
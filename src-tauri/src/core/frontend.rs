@@ -0,0 +1,24 @@
+use super::events::EventSink;
+use crate::ui::notification::Notifier;
+use crate::ui::window::WindowController;
+use std::sync::Arc;
+
+/// 后台任务使用的界面接口：事件输出、主窗口和桌面通知
+///
+/// 界面模式下由 `tauri::AppHandle` 实现，无界面模式见 `headless`。
+#[derive(Clone)]
+pub struct Frontend {
+    pub events: Arc<dyn EventSink>,
+    pub window: Arc<dyn WindowController>,
+    pub notifier: Arc<Notifier>,
+}
+
+impl Frontend {
+    pub fn tauri(app_handle: &tauri::AppHandle) -> Self {
+        Self {
+            events: Arc::new(app_handle.clone()),
+            window: Arc::new(app_handle.clone()),
+            notifier: Arc::new(Notifier::system(app_handle.clone())),
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use super::{background, frontend::Frontend, AppState};
+use crate::commands::app_state_snapshot;
+use crate::ui::window;
+use crate::utils::launch::LaunchOptions;
+use crate::utils::{config::ConfigUpdate, AppConfig};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, TryLockError};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use tracing::{debug, info, warn};
+
+/// 持有该文件的独占锁表示已有实例在运行
+const LOCK_FILE: &str = "instance.lock";
+
+/// 运行中实例的转发端口和口令，与锁文件分开保存，
+/// 因为 Windows 上被锁定的文件不能被其他进程读取
+const ENDPOINT_FILE: &str = "instance.json";
+
+/// 第二次启动时等待运行中实例写入端口的次数和间隔
+const FORWARD_ATTEMPTS: u32 = 20;
+const FORWARD_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 查询运行中实例状态和重新连接的子命令
+pub const STATUS_COMMAND: &str = "status";
+pub const RECONNECT_COMMAND: &str = "reconnect";
+
+/// 转发连接的读写超时
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 运行中实例的转发端口和口令
+#[derive(Serialize, Deserialize)]
+struct Endpoint {
+    port: u16,
+    nonce: String,
+}
+
+/// 第二次启动发送给运行中实例的请求
+#[derive(Serialize, Deserialize)]
+struct ForwardRequest {
+    nonce: String,
+    args: Vec<String>,
+}
+
+/// 获取单实例锁的结果
+pub enum Instance {
+    /// 当前进程是唯一实例，需要在启动后调用 `InstanceServer::start` 接收转发
+    Primary(InstanceServer),
+    /// 已有实例在运行，参数已转发，内容为运行中实例的回复
+    Forwarded(String),
+}
+
+/// 唯一实例持有的锁和转发监听端口，锁在进程退出时释放
+pub struct InstanceServer {
+    _lock: File,
+    listener: TcpListener,
+    nonce: String,
+}
+
+/// 运行中的实例可以直接应用的开关参数
+const FORWARDED_SWITCHES: &[&str] = &["/window_visible", "/auto_accept", "/auto_hide", "/mouse_through"];
+
+/// 锁文件和端口文件所在的目录，即配置文件所在的目录
+fn instance_dir() -> Result<PathBuf, String> {
+    AppConfig::config_path()
+        .map(|path| path.parent().map(Path::to_path_buf).unwrap_or_default())
+        .map_err(|e| format!("获取配置目录失败: {}", e))
+}
+
+fn open_lock_file(directory: &Path) -> Result<File, String> {
+    let path = directory.join(LOCK_FILE);
+    File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)
+        .map_err(|e| format!("打开实例锁文件失败: {}", e))
+}
+
+/// 获取单实例锁，已有实例运行时将启动参数转发给它
+///
+/// 使用同一配置目录的进程共享一把锁，`--config` 或便携模式指向其他目录时可以同时运行。
+pub fn acquire(args: &[String]) -> Result<Instance, String> {
+    acquire_in(&instance_dir()?, args)
+}
+
+fn acquire_in(directory: &Path, args: &[String]) -> Result<Instance, String> {
+    let lock = open_lock_file(directory)?;
+    match lock.try_lock() {
+        Ok(()) => InstanceServer::bind(lock, directory).map(Instance::Primary),
+        Err(TryLockError::WouldBlock) => forward(directory, args).map(Instance::Forwarded),
+        Err(TryLockError::Error(e)) => Err(format!("获取实例锁失败: {}", e)),
+    }
+}
+
+/// 已有实例运行时转发启动参数，返回转发结果，否则返回 None
+///
+/// 在以管理员权限重启之前调用，避免第二次启动时再次弹出权限提示。
+/// 只检查不持有锁，真正的锁由 `acquire` 获取。
+pub fn forward_if_running(args: &[String]) -> Option<Result<String, String>> {
+    let directory = instance_dir().ok()?;
+    let lock = open_lock_file(&directory).ok()?;
+    match lock.try_lock() {
+        Err(TryLockError::WouldBlock) => Some(forward(&directory, args)),
+        _ => None,
+    }
+}
+
+/// 将启动参数发送给运行中的实例并等待回复
+///
+/// 运行中的实例可能刚获取锁还未写入端口，或者端口文件是上次运行留下的，因此会重试几次。
+fn forward(directory: &Path, args: &[String]) -> Result<String, String> {
+    let path = directory.join(ENDPOINT_FILE);
+    let mut last_error = String::from("未找到运行中实例的端口");
+
+    for _ in 0..FORWARD_ATTEMPTS {
+        let endpoint = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<Endpoint>(&content).map_err(|e| e.to_string()));
+        match endpoint.and_then(|endpoint| send(&endpoint, args)) {
+            Ok(reply) => return reply,
+            Err(e) => last_error = e,
+        }
+        std::thread::sleep(FORWARD_RETRY_INTERVAL);
+    }
+    Err(format!("micept 已在运行，但无法转发启动参数: {}", last_error))
+}
+
+/// 发送一次请求，外层错误表示连接失败，内层为运行中实例的处理结果
+fn send(endpoint: &Endpoint, args: &[String]) -> Result<Result<String, String>, String> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, endpoint.port));
+    let mut stream = TcpStream::connect_timeout(&address, FORWARD_TIMEOUT).map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(FORWARD_TIMEOUT)).map_err(|e| e.to_string())?;
+
+    let request = ForwardRequest {
+        nonce: endpoint.nonce.clone(),
+        args: args.to_vec(),
+    };
+    let mut line = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream.write_all(line.as_bytes()).map_err(|e| e.to_string())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).map_err(|e| e.to_string())?;
+    serde_json::from_str(&reply).map_err(|e| format!("无法解析运行中实例的回复: {}", e))
+}
+
+impl InstanceServer {
+    /// 监听本地随机端口并写入端口文件
+    fn bind(lock: File, directory: &Path) -> Result<Self, String> {
+        let listener =
+            TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).map_err(|e| format!("监听转发端口失败: {}", e))?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+        let mut bytes = [0u8; 16];
+        getrandom::getrandom(&mut bytes).map_err(|e| format!("生成口令失败: {}", e))?;
+        let nonce: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+        let endpoint = serde_json::to_string(&Endpoint { port, nonce: nonce.clone() }).map_err(|e| e.to_string())?;
+        fs::write(directory.join(ENDPOINT_FILE), endpoint).map_err(|e| format!("写入实例端口失败: {}", e))?;
+        debug!(port, "单实例转发端口已就绪");
+
+        Ok(Self { _lock: lock, listener, nonce })
+    }
+
+    /// 在后台接收其他启动转发的参数，应用退出时停止
+    pub fn start(self, frontend: Frontend, state: AppState) {
+        let InstanceServer { _lock: lock, listener, nonce } = self;
+        let shutdown = state.shutdown.clone();
+        tauri::async_runtime::spawn(shutdown.clone().track(async move {
+            let listener = match listener
+                .set_nonblocking(true)
+                .and_then(|_| tokio::net::TcpListener::from_std(listener))
+            {
+                Ok(listener) => listener,
+                Err(e) => {
+                    warn!("启动单实例转发失败: {}", e);
+                    return;
+                }
+            };
+
+            loop {
+                let stream = tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => stream,
+                        Err(e) => {
+                            warn!("接收转发连接失败: {}", e);
+                            continue;
+                        }
+                    },
+                };
+                match tokio::time::timeout(FORWARD_TIMEOUT, handle(stream, &nonce, &frontend, &state)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => warn!("处理转发的启动参数失败: {}", e),
+                    Err(_) => warn!("处理转发的启动参数超时"),
+                }
+            }
+            drop(lock);
+        }));
+    }
+}
+
+/// 读取一条转发请求，执行后回复结果
+async fn handle(stream: tokio::net::TcpStream, nonce: &str, frontend: &Frontend, state: &AppState) -> Result<(), String> {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    tokio::io::BufReader::new(reader)
+        .read_line(&mut line)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let request: ForwardRequest = serde_json::from_str(&line).map_err(|e| e.to_string())?;
+    if request.nonce != nonce {
+        return Err("口令不匹配".to_string());
+    }
+
+    info!(args = ?request.args, "收到第二次启动转发的参数");
+    let reply = apply_args(frontend, state, &request.args);
+    let mut reply = serde_json::to_string(&reply).map_err(|e| e.to_string())?;
+    reply.push('\n');
+    writer.write_all(reply.as_bytes()).await.map_err(|e| e.to_string())
+}
+
+/// 在运行中的实例上执行转发的参数，没有参数时显示窗口，`--minimized` 和 `--headless` 时什么也不做
+///
+/// 只支持开关类参数，其他配置项需要修改配置文件。开关参数与启动时一样只在本次运行中生效，不写入配置文件。
+/// `status` 和 `reconnect` 子命令也通过这里发给运行中的实例。
+fn apply_args(frontend: &Frontend, state: &AppState, args: &[String]) -> Result<String, String> {
+    match args.first().map(String::as_str) {
+        Some(STATUS_COMMAND) => {
+            return serde_json::to_string_pretty(&app_state_snapshot(state)).map_err(|e| e.to_string());
+        }
+        Some(RECONNECT_COMMAND) => {
+            background::restart(state.clone())?;
+            return Ok("正在重新连接".to_string());
+        }
+        _ => {}
+    }
+
+    let options = LaunchOptions::parse(args.iter().cloned(), std::iter::empty())?;
+    for item in &options.overrides {
+        if !FORWARDED_SWITCHES.contains(&item.pointer.as_str()) {
+            return Err(format!("运行中的实例不支持参数: {}", item.source));
+        }
+        item.value
+            .parse::<bool>()
+            .map_err(|_| format!("{} 的值应为 true 或 false", item.source))?;
+    }
+
+    if options.overrides.is_empty() {
+        // 开机自启的最小化启动和无界面启动遇到运行中的实例时不打扰用户
+        if !options.minimized && !options.headless {
+            window::set_window_visible(frontend.window.as_ref(), state, true);
+        }
+        return Ok("已转发到运行中的 micept".to_string());
+    }
+
+    state.update_config(ConfigUpdate::LaunchOverrides(options.overrides.clone()));
+    let (visible, mouse_through) = state.read(|snapshot| (snapshot.config.window_visible, snapshot.config.mouse_through));
+    for item in &options.overrides {
+        let result = match item.pointer.as_str() {
+            "/window_visible" => frontend
+                .window
+                .set_visible(visible)
+                .and_then(|_| if visible { frontend.window.focus() } else { Ok(()) }),
+            "/mouse_through" => frontend.window.set_mouse_through(mouse_through),
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            warn!("应用转发的参数{}失败: {}", item.source, e);
+        }
+    }
+    Ok("已转发到运行中的 micept".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, temp_dir, FakeWindow, Recorder};
+    use std::sync::Arc;
+
+    fn frontend(window: Arc<FakeWindow>) -> Frontend {
+        test_support::frontend(&Recorder::default(), &Recorder::default(), window)
+    }
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn read_endpoint(directory: &Path) -> Endpoint {
+        serde_json::from_str(&fs::read_to_string(directory.join(ENDPOINT_FILE)).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn primary_writes_endpoint_and_releases_lock_on_drop() {
+        let directory = temp_dir("instance-lock");
+
+        let Ok(Instance::Primary(server)) = acquire_in(&directory, &[]) else {
+            panic!("第一次启动应获得锁");
+        };
+        let endpoint = read_endpoint(&directory);
+        assert_eq!(endpoint.port, server.listener.local_addr().unwrap().port());
+        assert_eq!(endpoint.nonce, server.nonce);
+        assert_eq!(endpoint.nonce.len(), 32);
+        assert!(endpoint.nonce.chars().all(|c| c.is_ascii_hexdigit()));
+
+        // 另一个句柄无法获得锁
+        assert!(matches!(open_lock_file(&directory).unwrap().try_lock(), Err(TryLockError::WouldBlock)));
+
+        drop(server);
+        assert!(matches!(acquire_in(&directory, &[]), Ok(Instance::Primary(_))));
+        let _ = fs::remove_dir_all(directory);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn second_launch_forwards_args_to_primary() {
+        let directory = temp_dir("instance-forward");
+        let state = AppState::with_config(AppConfig::default());
+        let window = Arc::new(FakeWindow::default());
+
+        let Ok(Instance::Primary(server)) = acquire_in(&directory, &[]) else {
+            panic!("第一次启动应获得锁");
+        };
+        server.start(frontend(window.clone()), state.clone());
+
+        let second = directory.clone();
+        let reply = tokio::task::spawn_blocking(move || acquire_in(&second, &args(&["--no-auto-accept"])))
+            .await
+            .unwrap();
+        let Ok(Instance::Forwarded(reply)) = reply else {
+            panic!("第二次启动应转发参数");
+        };
+        assert_eq!(reply, "已转发到运行中的 micept");
+        state.read(|snapshot| {
+            assert!(!snapshot.config.auto_accept());
+            // 与启动参数一样不写入配置文件
+            assert_eq!(snapshot.config.persisted_value().unwrap()["profiles"]["default"]["auto_accept"], true);
+        });
+        assert!(window.calls.lock().unwrap().is_empty());
+
+        let second = directory.clone();
+        let status = tokio::task::spawn_blocking(move || forward(&second, &args(&[STATUS_COMMAND])))
+            .await
+            .unwrap()
+            .unwrap();
+        let status: serde_json::Value = serde_json::from_str(&status).unwrap();
+        assert_eq!(status["auto_accept"], false);
+
+        assert!(state.shutdown.run(Duration::from_secs(1)).await);
+        let _ = fs::remove_dir_all(directory);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn wrong_nonce_is_rejected() {
+        let directory = temp_dir("instance-nonce");
+        let state = AppState::with_config(AppConfig::default());
+
+        let Ok(Instance::Primary(server)) = acquire_in(&directory, &[]) else {
+            panic!("第一次启动应获得锁");
+        };
+        server.start(frontend(Arc::default()), state.clone());
+
+        let endpoint = Endpoint {
+            port: read_endpoint(&directory).port,
+            nonce: "0".repeat(32),
+        };
+        let result = tokio::task::spawn_blocking(move || send(&endpoint, &args(&["--no-auto-accept"])))
+            .await
+            .unwrap();
+        assert!(result.is_err(), "{:?}", result);
+        assert!(state.read(|snapshot| snapshot.config.auto_accept()));
+
+        assert!(state.shutdown.run(Duration::from_secs(1)).await);
+        let _ = fs::remove_dir_all(directory);
+    }
+
+    #[test]
+    fn apply_args_handles_switches_and_window() {
+        let state = AppState::with_config(AppConfig::default());
+        let window = Arc::new(FakeWindow::default());
+        let frontend = frontend(window.clone());
+
+        // 没有参数时显示窗口，开机自启和无界面启动时不打扰
+        apply_args(&frontend, &state, &[]).unwrap();
+        apply_args(&frontend, &state, &args(&["--minimized"])).unwrap();
+        apply_args(&frontend, &state, &args(&["--headless"])).unwrap();
+        assert_eq!(*window.calls.lock().unwrap(), vec![true]);
+
+        apply_args(&frontend, &state, &args(&["--no-mouse-through", "--auto-hide", "--no-window"])).unwrap();
+        state.read(|snapshot| {
+            assert!(!snapshot.config.mouse_through);
+            assert!(snapshot.config.auto_hide);
+            assert!(!snapshot.config.window_visible);
+            let persisted = snapshot.config.persisted_value().unwrap();
+            assert_eq!(persisted["mouse_through"], true);
+            assert_eq!(persisted["auto_hide"], false);
+            assert_eq!(persisted["window_visible"], true);
+        });
+        assert_eq!(*window.calls.lock().unwrap(), vec![true, false]);
+
+        let error = apply_args(&frontend, &state, &args(&["--auto-accept=maybe"])).unwrap_err();
+        assert!(error.contains("--auto-accept"), "{}", error);
+        let error = apply_args(&frontend, &state, &args(&["--api.port=18000"])).unwrap_err();
+        assert!(error.contains("不支持"), "{}", error);
+    }
+}
@@ -1,5 +1,15 @@
 /// 核心模块 - 应用状态管理和后台任务
 pub mod state;
 pub mod background;
+pub mod session;
+pub mod events;
+pub mod config_writer;
+pub mod config_watcher;
+pub mod state_sync;
+pub mod shutdown;
+pub mod diagnostics;
+pub mod instance;
+pub mod frontend;
+pub mod profiles;
 
 pub use state::AppState;
\ No newline at end of file
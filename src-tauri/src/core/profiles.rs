@@ -0,0 +1,27 @@
+use super::{events::{self, EventSink}, AppState};
+use crate::utils::config::ConfigUpdate;
+use tracing::info;
+
+/// 切换配置档案，自动接受状态随档案切换
+///
+/// `puuid` 为 None 时切换回默认档案，首次出现的账号会以默认档案为模板创建。
+/// 后台任务在检测到账号变化时调用，托盘菜单用于手动切换。
+pub fn switch_profile(sink: &dyn EventSink, state: &AppState, puuid: Option<String>, name: &str) {
+    state.update_config(ConfigUpdate::ActiveProfile {
+        puuid: puuid.clone(),
+        name: name.to_string(),
+    });
+
+    let (auto_accept, profile_name) = state.read(|snapshot| {
+        let config = &snapshot.config;
+        (config.auto_accept(), config.profiles.active_profile().name.clone())
+    });
+
+    info!("Profile switched to: {}", profile_name);
+    events::emit(
+        sink,
+        state,
+        "profile-switched",
+        serde_json::json!({ "puuid": puuid, "name": profile_name, "auto_accept": auto_accept }),
+    );
+}
@@ -0,0 +1,248 @@
+use super::{events::{self, EventSink}, AppState};
+use crate::lol::RankedStats;
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+use std::time::Instant;
+
+/// 本次会话的统计结果，通过 `session-summary` 事件发送给前端
+///
+/// `games` 统计所有结束的对局，胜负、胜点和升降级只来自排位数据的变化。
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct SessionSummary {
+    pub date: String,
+    /// 所有模式的对局数
+    pub games: u32,
+    /// 排位胜场
+    pub wins: u32,
+    /// 排位负场
+    pub losses: u32,
+    pub net_lp: i32,
+    pub promotions: u32,
+    pub demotions: u32,
+    pub queue_seconds: u64,
+    pub game_seconds: u64,
+}
+
+impl SessionSummary {
+    /// 托盘提示文本
+    pub fn tooltip(&self) -> String {
+        let mut text = format!(
+            "micept\n今日 {}局 排位 {}胜 {}负 胜点 {:+}",
+            self.games, self.wins, self.losses, self.net_lp
+        );
+        if self.promotions > 0 || self.demotions > 0 {
+            text.push_str(&format!("\n晋级 {} 降级 {}", self.promotions, self.demotions));
+        }
+        text.push_str(&format!(
+            "\n排队 {}分钟 游戏 {}分钟",
+            self.queue_seconds / 60,
+            self.game_seconds / 60
+        ));
+        text
+    }
+}
+
+/// 对会话统计的修改，通过 `StateAction::Session` 执行
+#[derive(Clone)]
+pub enum SessionAction {
+    /// 跨天时重置
+    RollOver,
+    /// 手动重置
+    Reset,
+    /// 排位数据采样
+    Sample(RankedStats),
+    /// 一局游戏结束，附带结束后的排位数据
+    GameEnd(Option<RankedStats>),
+}
+
+/// 会话统计器，保存在状态快照中
+///
+/// 在启动时和每局 `EndOfGame` 之后采样排位数据，用相邻两次采样的差值累计胜负与胜点；
+/// 同时根据游戏流程阶段的切换累计排队时间和游戏时间。
+#[derive(Clone, PartialEq)]
+pub struct SessionTracker {
+    summary: SessionSummary,
+    date: NaiveDate,
+    last_sample: Option<RankedStats>,
+    current_phase: Option<(String, Instant)>,
+}
+
+impl SessionTracker {
+    pub fn new() -> Self {
+        let date = Local::now().date_naive();
+        Self {
+            summary: SessionSummary {
+                date: date.to_string(),
+                ..Default::default()
+            },
+            date,
+            last_sample: None,
+            current_phase: None,
+        }
+    }
+
+    /// 当前统计结果，包含正在进行中的阶段已经经过的时间
+    pub fn summary(&self) -> SessionSummary {
+        let mut summary = self.summary.clone();
+        if let Some((phase, since)) = &self.current_phase {
+            add_phase_time(&mut summary, phase, since.elapsed().as_secs());
+        }
+        summary
+    }
+
+    /// 当前所处阶段已经经过的秒数
+    pub fn phase_elapsed_seconds(&self) -> u64 {
+        self.current_phase
+            .as_ref()
+            .map(|(_, since)| since.elapsed().as_secs())
+            .unwrap_or(0)
+    }
+
+    /// 是否已经有排位数据作为基准
+    pub fn has_sample(&self) -> bool {
+        self.last_sample.is_some()
+    }
+
+    /// 清空统计，保留最近一次采样作为新的基准
+    pub fn reset(&mut self) {
+        self.date = Local::now().date_naive();
+        self.summary = SessionSummary {
+            date: self.date.to_string(),
+            ..Default::default()
+        };
+        if let Some((_, since)) = &mut self.current_phase {
+            *since = Instant::now();
+        }
+    }
+
+    /// 跨天时自动重置，返回是否发生了重置
+    pub fn roll_over_if_new_day(&mut self) -> bool {
+        if Local::now().date_naive() != self.date {
+            self.reset();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 记录一次排位数据采样，与上一次采样比较得出胜负和胜点变化
+    pub fn record_sample(&mut self, stats: RankedStats) {
+        if let Some(previous) = &self.last_sample {
+            for queue in &stats.queues {
+                let Some(old) = previous.queue(&queue.queue_type) else {
+                    continue;
+                };
+
+                self.summary.wins += queue.wins.saturating_sub(old.wins);
+                self.summary.losses += queue.losses.saturating_sub(old.losses);
+
+                if let (Some(old_score), Some(new_score)) = (old.rank_score(), queue.rank_score()) {
+                    self.summary.net_lp += new_score - old_score;
+                }
+
+                match (old.rank_key(), queue.rank_key()) {
+                    (Some(old_key), Some(new_key)) if new_key > old_key => self.summary.promotions += 1,
+                    (Some(old_key), Some(new_key)) if new_key < old_key => self.summary.demotions += 1,
+                    _ => {}
+                }
+            }
+        }
+        self.last_sample = Some(stats);
+    }
+
+    /// 记录一局游戏结束，排位数据获取失败时只累计局数
+    pub fn record_game_end(&mut self, stats: Option<RankedStats>) {
+        self.summary.games += 1;
+        if let Some(stats) = stats {
+            self.record_sample(stats);
+        }
+    }
+
+    /// 记录游戏流程阶段切换，累计上一阶段经过的时间
+    pub fn record_phase(&mut self, phase: &str) {
+        if let Some((previous, since)) = self.current_phase.take() {
+            add_phase_time(&mut self.summary, &previous, since.elapsed().as_secs());
+        }
+        self.current_phase = Some((phase.to_string(), Instant::now()));
+    }
+
+    /// 执行一项修改，返回统计是否变化
+    pub fn apply(&mut self, action: SessionAction) -> bool {
+        match action {
+            SessionAction::RollOver => return self.roll_over_if_new_day(),
+            SessionAction::Reset => self.reset(),
+            SessionAction::Sample(stats) => self.record_sample(stats),
+            SessionAction::GameEnd(stats) => self.record_game_end(stats),
+        }
+        true
+    }
+}
+
+impl Default for SessionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 将某阶段经过的时间计入排队或游戏时间
+fn add_phase_time(summary: &mut SessionSummary, phase: &str, seconds: u64) {
+    match phase {
+        "Matchmaking" | "ReadyCheck" => summary.queue_seconds += seconds,
+        "InProgress" | "Reconnect" => summary.game_seconds += seconds,
+        _ => {}
+    }
+}
+
+/// 向前端推送当前的会话统计，托盘提示由状态订阅任务根据快照刷新
+pub fn publish_summary(sink: &dyn EventSink, state: &AppState) {
+    let summary = state.read(|snapshot| snapshot.session.summary());
+    events::emit(sink, state, "session-summary", summary);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lol::RankedQueueStats;
+
+    fn solo(tier: &str, division: &str, league_points: i32, wins: u32, losses: u32) -> RankedStats {
+        RankedStats {
+            queues: vec![RankedQueueStats {
+                queue_type: "RANKED_SOLO_5x5".to_string(),
+                tier: tier.to_string(),
+                division: division.to_string(),
+                league_points,
+                wins,
+                losses,
+            }],
+        }
+    }
+
+    #[test]
+    fn samples_accumulate_ranked_results_across_promotion() {
+        let mut tracker = SessionTracker::new();
+        tracker.apply(SessionAction::Sample(solo("GOLD", "I", 90, 10, 8)));
+        tracker.apply(SessionAction::GameEnd(Some(solo("PLATINUM", "IV", 15, 11, 8))));
+        tracker.apply(SessionAction::GameEnd(Some(solo("GOLD", "I", 80, 11, 9))));
+
+        let summary = tracker.summary();
+        assert_eq!((summary.games, summary.wins, summary.losses), (2, 1, 1));
+        assert_eq!(summary.net_lp, -10);
+        assert_eq!((summary.promotions, summary.demotions), (1, 1));
+    }
+
+    #[test]
+    fn unranked_games_count_only_as_games() {
+        let mut tracker = SessionTracker::new();
+        tracker.apply(SessionAction::Sample(solo("GOLD", "I", 90, 10, 8)));
+        tracker.apply(SessionAction::GameEnd(None));
+        tracker.apply(SessionAction::GameEnd(Some(solo("GOLD", "I", 90, 10, 8))));
+
+        let summary = tracker.summary();
+        assert_eq!((summary.games, summary.wins, summary.losses, summary.net_lp), (2, 0, 0, 0));
+        assert!(summary.tooltip().contains("今日 2局 排位 0胜 0负"), "{}", summary.tooltip());
+
+        tracker.apply(SessionAction::Reset);
+        assert_eq!(tracker.summary().games, 0);
+        assert!(tracker.has_sample());
+    }
+}
@@ -0,0 +1,54 @@
+use super::AppState;
+use std::future::Future;
+use std::time::Duration;
+use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
+use tokio_util::task::{task_tracker::TrackedFuture, TaskTracker};
+use tracing::{info, warn};
+
+/// 退出时等待后台任务结束的最长时间
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 退出协调器
+///
+/// 后台任务、Webhook 队列、配置写入任务和本地API等服务共享同一个取消令牌，
+/// 退出时取消令牌并等待被跟踪的任务完成收尾工作。
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    token: CancellationToken,
+    tasks: TaskTracker,
+}
+
+impl Shutdown {
+    /// 跟踪一个任务，退出时等待它结束
+    pub fn track<F: Future>(&self, future: F) -> TrackedFuture<F> {
+        self.tasks.track_future(future)
+    }
+
+    /// 开始退出后完成
+    pub fn cancelled(&self) -> WaitForCancellationFuture<'_> {
+        self.token.cancelled()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// 取消令牌并等待被跟踪的任务结束，超时返回 false
+    pub async fn run(&self, timeout: Duration) -> bool {
+        self.token.cancel();
+        self.tasks.close();
+        tokio::time::timeout(timeout, self.tasks.wait()).await.is_ok()
+    }
+}
+
+/// 等待后台任务收尾后退出程序
+pub fn quit(app_handle: tauri::AppHandle, state: AppState) {
+    tauri::async_runtime::spawn(async move {
+        if state.shutdown.run(SHUTDOWN_TIMEOUT).await {
+            info!("后台任务已全部停止");
+        } else {
+            warn!("等待后台任务超时，强制退出");
+        }
+        app_handle.exit(0);
+    });
+}
@@ -1,32 +1,210 @@
-use crate::utils::AppConfig;
-use crate::lol::{LcuAuthInfo, SummonerInfo};
+use crate::utils::{config::ConfigUpdate, AppConfig};
+use crate::lol::{LcuAuthInfo, PlayerProfile, SummonerInfo};
+use super::events::{AppEvent, EVENT_BUS_CAPACITY};
+use super::background::BackgroundHandle;
+use super::session::{SessionAction, SessionTracker};
+use super::shutdown::Shutdown;
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc, watch};
+use tracing::warn;
+
+/// 保留的游戏阶段切换记录数量，用于诊断信息
+const PHASE_HISTORY_LIMIT: usize = 50;
+
+/// 一次游戏阶段切换
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseTransition {
+    pub at: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// 应用状态快照
+///
+/// 开关状态（鼠标穿透、自动接受、自动隐藏）只保存在 `config` 中。
+#[derive(Clone)]
+pub struct StateSnapshot {
+    pub config: AppConfig,
+    pub lcu_auth: Option<LcuAuthInfo>,
+    pub gameflow_phase: String,
+    pub summoner_info: Option<SummonerInfo>,
+    pub player_profile: Option<PlayerProfile>,
+    /// 最近的游戏阶段切换，最早的在前
+    pub phase_history: VecDeque<PhaseTransition>,
+    /// 今日会话统计，游戏阶段切换时自动累计时间
+    pub session: SessionTracker,
+    /// 本次运行中最近登录的账号 puuid，重启后台任务和断开连接时保留
+    pub account_puuid: Option<String>,
+}
+
+/// 对应用状态的修改，全部通过 `AppState::dispatch` 执行
+#[derive(Clone)]
+pub enum StateAction {
+    /// 修改配置并写入文件
+    Config(ConfigUpdate),
+    /// 替换为从文件重新加载的配置，不再写回文件
+    ReloadConfig(Box<AppConfig>),
+    LcuConnected(LcuAuthInfo),
+    /// 断开连接时清除认证信息、召唤师信息、玩家资料和游戏阶段
+    LcuDisconnected,
+    GameflowPhase(String),
+    SummonerInfo(Option<SummonerInfo>),
+    PlayerProfile(Option<PlayerProfile>),
+    Session(SessionAction),
+    /// 检测到客户端登录的账号，与本次运行中上一个账号不同时快照才会变化
+    AccountDetected(String),
+}
+
+impl StateAction {
+    /// 将修改应用到快照，返回快照是否变化
+    fn reduce(self, snapshot: &mut StateSnapshot) -> bool {
+        match self {
+            StateAction::Config(update) => {
+                let before = snapshot.config.clone();
+                update.apply(&mut snapshot.config);
+                before != snapshot.config
+            }
+            StateAction::ReloadConfig(config) => {
+                snapshot.config = *config;
+                true
+            }
+            StateAction::LcuConnected(auth) => {
+                snapshot.lcu_auth = Some(auth);
+                true
+            }
+            StateAction::LcuDisconnected => {
+                let modified = snapshot.lcu_auth.is_some();
+                snapshot.lcu_auth = None;
+                snapshot.summoner_info = None;
+                snapshot.player_profile = None;
+                snapshot.set_phase("None".to_string()) || modified
+            }
+            StateAction::GameflowPhase(phase) => snapshot.set_phase(phase),
+            StateAction::SummonerInfo(summoner) => {
+                let modified = snapshot.summoner_info.is_some() || summoner.is_some();
+                snapshot.summoner_info = summoner;
+                modified
+            }
+            StateAction::PlayerProfile(profile) => {
+                let modified = snapshot.player_profile.is_some() || profile.is_some();
+                snapshot.player_profile = profile;
+                modified
+            }
+            StateAction::Session(action) => snapshot.session.apply(action),
+            StateAction::AccountDetected(puuid) => {
+                let modified = snapshot.account_puuid.as_ref() != Some(&puuid);
+                snapshot.account_puuid = Some(puuid);
+                modified
+            }
+        }
+    }
+}
+
+impl StateSnapshot {
+    /// 切换游戏阶段并记录到阶段历史和会话统计，返回阶段是否变化
+    fn set_phase(&mut self, phase: String) -> bool {
+        if self.gameflow_phase == phase {
+            return false;
+        }
+        self.session.record_phase(&phase);
+        if self.phase_history.len() == PHASE_HISTORY_LIMIT {
+            self.phase_history.pop_front();
+        }
+        self.phase_history.push_back(PhaseTransition {
+            at: chrono::Local::now().to_rfc3339(),
+            from: std::mem::replace(&mut self.gameflow_phase, phase),
+            to: self.gameflow_phase.clone(),
+        });
+        true
+    }
+}
 
 /// 应用状态管理器
+///
+/// 状态保存在 `watch` 通道中，订阅者在每次变化后收到通知，
+/// 前端事件和托盘菜单由 `state_sync` 任务统一更新。
 #[derive(Clone)]
 pub struct AppState {
-    pub mouse_through: Arc<Mutex<bool>>,
-    pub auto_accept: Arc<Mutex<bool>>,
-    pub auto_hide: Arc<Mutex<bool>>,
-    pub lcu_auth: Arc<Mutex<Option<LcuAuthInfo>>>,
-    pub gameflow_phase: Arc<Mutex<String>>,
-    pub summoner_info: Arc<Mutex<Option<SummonerInfo>>>,
-    pub is_running: Arc<Mutex<bool>>,
-    pub config: Arc<Mutex<AppConfig>>,
+    store: Arc<watch::Sender<StateSnapshot>>,
+    /// LCU 后台任务的句柄和健康状况
+    pub background: Arc<Mutex<BackgroundHandle>>,
+    pub shutdown: Shutdown,
+    pub events: broadcast::Sender<AppEvent>,
+    pub config_updates: mpsc::UnboundedSender<ConfigUpdate>,
+    /// 配置写入任务启动时取走
+    pub config_update_receiver: Arc<Mutex<Option<mpsc::UnboundedReceiver<ConfigUpdate>>>>,
+    /// 最近一次由本程序写入的配置内容，用于忽略自己保存触发的文件变化
+    pub config_last_saved: Arc<Mutex<Option<String>>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
-        let config = AppConfig::load();
+        Self::with_config(AppConfig::load())
+    }
+
+    /// 使用给定的配置创建状态，不读取配置文件
+    pub fn with_config(config: AppConfig) -> Self {
+        let snapshot = StateSnapshot {
+            config,
+            lcu_auth: None,
+            gameflow_phase: "None".to_string(),
+            summoner_info: None,
+            player_profile: None,
+            phase_history: VecDeque::new(),
+            session: SessionTracker::new(),
+            account_puuid: None,
+        };
+        let (config_updates, config_update_receiver) = mpsc::unbounded_channel();
         Self {
-            mouse_through: Arc::new(Mutex::new(config.mouse_through)),
-            auto_accept: Arc::new(Mutex::new(config.auto_accept)),
-            auto_hide: Arc::new(Mutex::new(config.auto_hide)),
-            lcu_auth: Arc::new(Mutex::new(None)),
-            gameflow_phase: Arc::new(Mutex::new("None".to_string())),
-            summoner_info: Arc::new(Mutex::new(None)),
-            is_running: Arc::new(Mutex::new(true)),
-            config: Arc::new(Mutex::new(config)),
+            store: Arc::new(watch::Sender::new(snapshot)),
+            background: Arc::new(Mutex::new(BackgroundHandle::default())),
+            shutdown: Shutdown::default(),
+            events: broadcast::channel(EVENT_BUS_CAPACITY).0,
+            config_updates,
+            config_update_receiver: Arc::new(Mutex::new(Some(config_update_receiver))),
+            config_last_saved: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// 读取当前状态
+    ///
+    /// 闭包执行期间持有读锁，不能在其中调用 `dispatch`。
+    pub fn read<R>(&self, f: impl FnOnce(&StateSnapshot) -> R) -> R {
+        f(&self.store.borrow())
+    }
+
+    /// 当前状态的副本
+    pub fn snapshot(&self) -> StateSnapshot {
+        self.store.borrow().clone()
+    }
+
+    /// 订阅状态变化
+    pub fn subscribe(&self) -> watch::Receiver<StateSnapshot> {
+        self.store.subscribe()
+    }
+
+    /// 执行一项修改，状态变化时通知订阅者，返回状态是否变化
+    ///
+    /// 配置修改会同时交给后台任务写入文件。
+    pub fn dispatch(&self, action: StateAction) -> bool {
+        let update = match &action {
+            StateAction::Config(update) => Some(update.clone()),
+            _ => None,
+        };
+        let modified = self.store.send_if_modified(|snapshot| action.reduce(snapshot));
+
+        if let (true, Some(update)) = (modified, update) {
+            if let Err(e) = self.config_updates.send(update) {
+                warn!("配置写入任务已停止，修改未保存: {:?}", e.0);
+            }
         }
+        modified
+    }
+
+    /// 修改配置，等同于 `dispatch(StateAction::Config(update))`
+    pub fn update_config(&self, update: ConfigUpdate) {
+        self.dispatch(StateAction::Config(update));
     }
-}
\ No newline at end of file
+}
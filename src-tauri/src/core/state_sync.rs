@@ -0,0 +1,67 @@
+use super::{events, state::StateSnapshot, AppState};
+use crate::commands::app_state_snapshot;
+use crate::ui::tray;
+
+/// 托盘菜单依赖的状态，变化时才重新构建菜单
+#[derive(PartialEq)]
+struct TrayMenuState {
+    mouse_through: bool,
+    auto_accept: bool,
+    auto_hide: bool,
+    discord_enabled: bool,
+    autostart: bool,
+    active_profile: Option<String>,
+    profile_names: Vec<String>,
+}
+
+impl TrayMenuState {
+    fn from_snapshot(snapshot: &StateSnapshot) -> Self {
+        let config = &snapshot.config;
+        let profiles = &config.profiles;
+        Self {
+            mouse_through: config.mouse_through,
+            auto_accept: config.auto_accept(),
+            auto_hide: config.auto_hide,
+            discord_enabled: config.discord.enabled,
+            autostart: config.autostart,
+            active_profile: profiles.active.clone(),
+            profile_names: std::iter::once(&profiles.default)
+                .chain(profiles.accounts.values())
+                .map(|profile| profile.name.clone())
+                .collect(),
+        }
+    }
+}
+
+/// 订阅状态变化，向前端发送 `state-changed` 事件并更新托盘菜单和提示
+///
+/// 每次修改通常对应一次通知，只有处理不及时时 watch 通道才会把多次修改合并为一次。
+pub fn start(app_handle: tauri::AppHandle, state: AppState) {
+    let mut receiver = state.subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        let (mut tray_menu, mut tooltip) = {
+            let snapshot = receiver.borrow_and_update();
+            (TrayMenuState::from_snapshot(&snapshot), snapshot.session.summary().tooltip())
+        };
+
+        while receiver.changed().await.is_ok() {
+            let (current, config, current_tooltip) = {
+                let snapshot = receiver.borrow_and_update();
+                let current = TrayMenuState::from_snapshot(&snapshot);
+                let config = (current != tray_menu).then(|| snapshot.config.clone());
+                (current, config, snapshot.session.summary().tooltip())
+            };
+
+            events::emit(&app_handle, &state, "state-changed", app_state_snapshot(&state));
+            if let Some(config) = config {
+                tray::update_tray_menu(&app_handle, &config);
+                tray_menu = current;
+            }
+            if current_tooltip != tooltip {
+                tray::update_tray_tooltip(&app_handle, &current_tooltip);
+                tooltip = current_tooltip;
+            }
+        }
+    });
+}
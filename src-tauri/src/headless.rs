@@ -0,0 +1,92 @@
+use crate::app::start_services;
+use crate::core::{
+    events::EventSink,
+    frontend::Frontend,
+    instance::InstanceServer,
+    shutdown::SHUTDOWN_TIMEOUT,
+    AppState,
+};
+use crate::ui::{
+    notification::{NotificationSink, Notifier},
+    window::WindowController,
+};
+use chrono::Local;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// 将事件逐行打印到标准输出：时间、事件名称和 JSON 内容
+struct StdoutEventSink;
+
+impl EventSink for StdoutEventSink {
+    fn send(&self, name: &'static str, payload: &serde_json::Value) {
+        println!("{} {} {}", Local::now().format("%H:%M:%S"), name, payload);
+    }
+}
+
+/// 桌面通知改为打印到标准输出
+struct StdoutNotificationSink;
+
+impl NotificationSink for StdoutNotificationSink {
+    fn show(&self, title: &str, body: &str) {
+        println!("{} 通知 {}: {}", Local::now().format("%H:%M:%S"), title, body);
+    }
+}
+
+/// 没有窗口，窗口操作直接返回成功
+struct NoWindow;
+
+impl WindowController for NoWindow {
+    fn is_visible(&self) -> bool {
+        false
+    }
+
+    fn set_visible(&self, _visible: bool) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn focus(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn set_mouse_through(&self, _enabled: bool) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn set_position(&self, _x: i32, _y: i32) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// 无界面模式：不创建窗口和托盘，只运行后台任务、配置读写和各项集成
+///
+/// 事件和通知打印到标准输出，通过 `micept status`、`micept reconnect`、
+/// 再次启动时的开关参数或本地API控制，按 Ctrl+C 退出。
+pub fn run(instance: InstanceServer, state: AppState) {
+    let frontend = Frontend {
+        events: Arc::new(StdoutEventSink),
+        window: Arc::new(NoWindow),
+        notifier: Arc::new(Notifier::with_system_sound(Box::new(StdoutNotificationSink))),
+    };
+
+    tauri::async_runtime::block_on(async move {
+        start_services(frontend, &state, instance);
+        info!("无界面模式已启动");
+        println!("micept 已在无界面模式下运行，按 Ctrl+C 退出，使用 `micept status` 查看状态");
+
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                if let Err(e) = result {
+                    warn!("监听退出信号失败: {}", e);
+                }
+            }
+            _ = state.shutdown.cancelled() => {}
+        }
+
+        println!("正在退出...");
+        if state.shutdown.run(SHUTDOWN_TIMEOUT).await {
+            info!("后台任务已全部停止");
+        } else {
+            warn!("等待后台任务超时，强制退出");
+        }
+    });
+}
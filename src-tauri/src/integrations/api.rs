@@ -0,0 +1,331 @@
+use crate::commands::app_state_snapshot;
+use crate::core::{background, frontend::Frontend, AppState};
+use crate::ui::tray;
+use crate::utils::{redact, AppConfig};
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use futures_util::stream::{self, Stream};
+use serde::Deserialize;
+use std::fs;
+use std::io::Write;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::Path;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+/// 本地API的共享上下文
+#[derive(Clone)]
+struct ApiContext {
+    frontend: Frontend,
+    state: AppState,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct ToggleRequest {
+    enabled: bool,
+}
+
+/// 读取保存在配置文件旁的访问令牌，不存在时生成一个新的
+pub fn load_or_create_token() -> Result<String, Box<dyn std::error::Error>> {
+    let path = AppConfig::config_path()?.with_file_name("api_token");
+    if let Ok(token) = fs::read_to_string(&path) {
+        let token = token.trim();
+        if !token.is_empty() {
+            redact::register(token);
+            // 旧版本创建的令牌文件可能对其他用户可读
+            if let Err(e) = restrict_to_owner(&path) {
+                warn!("限制访问令牌文件权限失败: {}", e);
+            }
+            return Ok(token.to_string());
+        }
+    }
+
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("生成访问令牌失败: {}", e))?;
+    let token: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    redact::register(&token);
+    write_token(&path, &token)?;
+    info!("已生成本地API访问令牌: {:?}", path);
+    Ok(token)
+}
+
+/// 写入只有当前用户可以读写的令牌文件，先限制权限再写入内容
+fn write_token(path: &Path, token: &str) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    restrict_to_owner(path)?;
+    file.write_all(token.as_bytes())
+}
+
+/// 将文件权限限制为只有所有者可以读写
+#[cfg(unix)]
+fn restrict_to_owner(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+}
+
+/// 将文件的访问控制列表替换为只允许所有者访问，不再继承目录的权限
+#[cfg(windows)]
+fn restrict_to_owner(path: &Path) -> std::io::Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::LocalFree;
+    use windows_sys::Win32::Security::Authorization::{
+        ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+    };
+    use windows_sys::Win32::Security::{
+        SetFileSecurityW, DACL_SECURITY_INFORMATION, PROTECTED_DACL_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR,
+    };
+
+    let wide = |value: &std::ffi::OsStr| value.encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+    // 受保护的 DACL，只授予所有者完全控制
+    let sddl = wide("D:P(A;;FA;;;OW)".as_ref());
+    let path = wide(path.as_os_str());
+    let mut descriptor: PSECURITY_DESCRIPTOR = std::ptr::null_mut();
+    // SAFETY: 字符串均以 0 结尾，转换得到的安全描述符在使用后用 LocalFree 释放
+    unsafe {
+        if ConvertStringSecurityDescriptorToSecurityDescriptorW(
+            sddl.as_ptr(),
+            SDDL_REVISION_1,
+            &mut descriptor,
+            std::ptr::null_mut(),
+        ) == 0
+        {
+            return Err(std::io::Error::last_os_error());
+        }
+        let applied = SetFileSecurityW(
+            path.as_ptr(),
+            DACL_SECURITY_INFORMATION | PROTECTED_DACL_SECURITY_INFORMATION,
+            descriptor,
+        );
+        let error = std::io::Error::last_os_error();
+        LocalFree(descriptor);
+        if applied == 0 {
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn restrict_to_owner(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// 启动本地 HTTP 控制接口，只监听 127.0.0.1
+pub fn start(frontend: Frontend, state: AppState, port: u16) {
+    let token = match load_or_create_token() {
+        Ok(token) => token,
+        Err(e) => {
+            warn!("本地API启动失败: {}", e);
+            return;
+        }
+    };
+    let shutdown = state.shutdown.clone();
+    let context = ApiContext { frontend, state, token };
+
+    tauri::async_runtime::spawn(shutdown.clone().track(async move {
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let listener = match tokio::net::TcpListener::bind(address).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("本地API监听端口{}失败: {}", port, e);
+                return;
+            }
+        };
+        info!("本地API已启动: http://{}", address);
+
+        let stopped = async move { shutdown.cancelled().await };
+        if let Err(e) = axum::serve(listener, router(context)).with_graceful_shutdown(stopped).await {
+            warn!("本地API异常退出: {}", e);
+        }
+    }));
+}
+
+fn router(context: ApiContext) -> Router {
+    Router::new()
+        .route("/api/state", get(get_state))
+        .route("/api/auto-accept", post(set_auto_accept))
+        .route("/api/mouse-through", post(set_mouse_through))
+        .route("/api/auto-hide", post(set_auto_hide))
+        .route("/api/reconnect", post(reconnect))
+        .route("/api/events", get(events))
+        .layer(middleware::from_fn_with_state(context.clone(), require_token))
+        .with_state(context)
+}
+
+/// 校验 `Authorization: Bearer <token>` 请求头
+async fn require_token(State(context): State<ApiContext>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| token_matches(token, &context.token));
+
+    if authorized {
+        next.run(request).await
+    } else {
+        (StatusCode::UNAUTHORIZED, "无效的访问令牌").into_response()
+    }
+}
+
+/// 比较访问令牌，耗时与令牌内容无关，避免通过响应时间逐字节猜出令牌
+fn token_matches(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+async fn get_state(State(context): State<ApiContext>) -> Json<serde_json::Value> {
+    Json(app_state_snapshot(&context.state))
+}
+
+async fn set_auto_accept(
+    State(context): State<ApiContext>,
+    Json(request): Json<ToggleRequest>,
+) -> Json<serde_json::Value> {
+    tray::set_auto_accept(&context.state, request.enabled);
+    Json(app_state_snapshot(&context.state))
+}
+
+async fn set_mouse_through(
+    State(context): State<ApiContext>,
+    Json(request): Json<ToggleRequest>,
+) -> Json<serde_json::Value> {
+    tray::set_mouse_through(context.frontend.window.as_ref(), &context.state, request.enabled);
+    Json(app_state_snapshot(&context.state))
+}
+
+async fn set_auto_hide(
+    State(context): State<ApiContext>,
+    Json(request): Json<ToggleRequest>,
+) -> Json<serde_json::Value> {
+    tray::set_auto_hide(&context.state, request.enabled);
+    Json(app_state_snapshot(&context.state))
+}
+
+/// 立即重新连接英雄联盟客户端
+async fn reconnect(State(context): State<ApiContext>) -> Response {
+    match background::restart(context.state.clone()) {
+        Ok(()) => Json(app_state_snapshot(&context.state)).into_response(),
+        Err(e) => (StatusCode::CONFLICT, e).into_response(),
+    }
+}
+
+/// 以 Server-Sent Events 推送后台任务发出的事件
+async fn events(State(context): State<ApiContext>) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let receiver = context.state.events.subscribe();
+    let shutdown = context.state.shutdown.clone();
+    // 应用退出时结束推送，让服务可以正常关闭
+    let stream = stream::unfold((receiver, shutdown), |(mut receiver, shutdown)| async move {
+        loop {
+            let received = tokio::select! {
+                _ = shutdown.cancelled() => return None,
+                received = receiver.recv() => received,
+            };
+            match received {
+                Ok(event) => {
+                    let item = Event::default().event(event.name).json_data(&event.payload);
+                    return Some((item, (receiver, shutdown)));
+                }
+                // 订阅者落后时跳过丢失的事件
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, Recorder};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// 在随机端口上启动接口，返回端口
+    async fn serve(state: &AppState) -> u16 {
+        let context = ApiContext {
+            frontend: test_support::frontend(&Recorder::default(), &Recorder::default(), Arc::default()),
+            state: state.clone(),
+            token: "secret-token".to_string(),
+        };
+        let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let shutdown = state.shutdown.clone();
+        tokio::spawn(state.shutdown.track(async move {
+            let stopped = async move { shutdown.cancelled().await };
+            axum::serve(listener, router(context)).with_graceful_shutdown(stopped).await.unwrap();
+        }));
+        port
+    }
+
+    /// 发送一个 GET 请求，返回响应状态码
+    async fn status(port: u16, authorization: Option<&str>) -> u16 {
+        let mut stream = tokio::net::TcpStream::connect((Ipv4Addr::LOCALHOST, port)).await.unwrap();
+        let mut request = "GET /api/state HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\n".to_string();
+        if let Some(authorization) = authorization {
+            request.push_str(&format!("Authorization: {}\r\n", authorization));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response.split(' ').nth(1).unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn token_comparison_requires_exact_match() {
+        assert!(token_matches("secret-token", "secret-token"));
+        assert!(!token_matches("secret-tokeN", "secret-token"));
+        assert!(!token_matches("secret", "secret-token"));
+        assert!(!token_matches("", "secret-token"));
+    }
+
+    #[tokio::test]
+    async fn requests_require_valid_token() {
+        let state = AppState::with_config(AppConfig::default());
+        let port = serve(&state).await;
+
+        assert_eq!(status(port, None).await, 401);
+        assert_eq!(status(port, Some("Bearer wrong-token")).await, 401);
+        assert_eq!(status(port, Some("secret-token")).await, 401);
+        assert_eq!(status(port, Some("Bearer secret-token")).await, 200);
+
+        assert!(state.shutdown.run(std::time::Duration::from_secs(1)).await);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn token_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = test_support::temp_dir("api-token").join("api_token");
+        write_token(&path, "secret-token").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "secret-token");
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        restrict_to_owner(&path).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,392 @@
+use crate::core::AppState;
+use serde::Serialize;
+use serde_json::Value;
+use std::io;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+/// IPC 帧类型
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+const OP_CLOSE: u32 = 2;
+const OP_PING: u32 = 3;
+const OP_PONG: u32 = 4;
+
+/// 单帧最大长度，防止异常数据导致分配过多内存
+const MAX_FRAME_LEN: u32 = 64 * 1024;
+
+/// 等待 Discord 响应的最长时间，超时视为断开连接
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 未连接到 Discord 时的重试间隔，连续失败时逐次加倍
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 重试间隔的上限
+const MAX_RECONNECT_INTERVAL: Duration = Duration::from_secs(300);
+
+/// 展示在 Discord 上的活动状态
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Activity {
+    pub details: String,
+    pub state: Option<String>,
+    pub start_timestamp: i64,
+}
+
+impl Activity {
+    /// 根据游戏流程阶段生成活动状态，不需要展示的阶段返回 None
+    pub fn for_phase(phase: &str, queue_name: &str, champion: Option<&str>, start_timestamp: i64) -> Option<Self> {
+        let phase_text = match phase {
+            "Matchmaking" | "ReadyCheck" => "排队中",
+            "ChampSelect" => "英雄选择",
+            "InProgress" | "Reconnect" => "游戏中",
+            _ => return None,
+        };
+        let details = match champion {
+            Some(champion) => format!("{} · {}", phase_text, champion),
+            None => phase_text.to_string(),
+        };
+        Some(Self {
+            details,
+            state: Some(queue_name.to_string()).filter(|name| !name.is_empty()),
+            start_timestamp,
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "details": self.details,
+            "state": self.state,
+            "timestamps": { "start": self.start_timestamp },
+            "assets": { "large_image": "league", "large_text": "League of Legends" },
+        })
+    }
+}
+
+/// 编码一帧：小端序的帧类型和长度，后跟 JSON 数据
+pub fn encode_frame(opcode: u32, payload: &Value) -> Vec<u8> {
+    let body = payload.to_string().into_bytes();
+    let mut frame = Vec::with_capacity(8 + body.len());
+    frame.extend_from_slice(&opcode.to_le_bytes());
+    frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&body);
+    frame
+}
+
+/// 读取一帧
+pub async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<(u32, Value)> {
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header).await?;
+    let opcode = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    if length > MAX_FRAME_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Discord IPC帧过长"));
+    }
+
+    let mut body = vec![0u8; length as usize];
+    reader.read_exact(&mut body).await?;
+    let payload = serde_json::from_slice(&body)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Ok((opcode, payload))
+}
+
+/// Discord IPC 客户端
+pub struct DiscordIpc<S> {
+    stream: S,
+    nonce: u64,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> DiscordIpc<S> {
+    /// 发送握手并等待 READY 事件
+    pub async fn handshake(stream: S, client_id: &str) -> io::Result<Self> {
+        let mut ipc = Self { stream, nonce: 0 };
+        ipc.write_frame(OP_HANDSHAKE, &serde_json::json!({ "v": 1, "client_id": client_id }))
+            .await?;
+
+        let response = ipc.read_response().await?;
+        if response["evt"] != "READY" {
+            return Err(io::Error::other(format!("Discord握手失败: {}", response)));
+        }
+        Ok(ipc)
+    }
+
+    /// 设置活动状态，传入 None 时清除
+    pub async fn set_activity(&mut self, activity: Option<&Activity>) -> io::Result<()> {
+        self.nonce += 1;
+        let payload = serde_json::json!({
+            "cmd": "SET_ACTIVITY",
+            "args": {
+                "pid": std::process::id(),
+                "activity": activity.map(Activity::to_json),
+            },
+            "nonce": self.nonce.to_string(),
+        });
+        self.write_frame(OP_FRAME, &payload).await?;
+
+        let response = self.read_response().await?;
+        if response["evt"] == "ERROR" {
+            return Err(io::Error::other(format!("设置Discord状态失败: {}", response["data"])));
+        }
+        Ok(())
+    }
+
+    async fn write_frame(&mut self, opcode: u32, payload: &Value) -> io::Result<()> {
+        self.stream.write_all(&encode_frame(opcode, payload)).await?;
+        self.stream.flush().await
+    }
+
+    /// 读取下一条响应，自动回复心跳，`RESPONSE_TIMEOUT` 内没有收到时返回 `TimedOut`
+    async fn read_response(&mut self) -> io::Result<Value> {
+        loop {
+            let (opcode, payload) = tokio::time::timeout(RESPONSE_TIMEOUT, read_frame(&mut self.stream))
+                .await
+                .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "等待Discord响应超时"))??;
+            match opcode {
+                OP_PING => self.write_frame(OP_PONG, &payload).await?,
+                OP_CLOSE => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        format!("Discord关闭了连接: {}", payload),
+                    ))
+                }
+                _ => return Ok(payload),
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+type IpcStream = tokio::net::UnixStream;
+
+#[cfg(windows)]
+type IpcStream = tokio::net::windows::named_pipe::NamedPipeClient;
+
+/// 依次尝试 `discord-ipc-0` 到 `discord-ipc-9`
+async fn connect() -> io::Result<IpcStream> {
+    for slot in 0..10 {
+        #[cfg(unix)]
+        let result = tokio::net::UnixStream::connect(socket_dir().join(format!("discord-ipc-{}", slot))).await;
+
+        #[cfg(windows)]
+        let result = tokio::net::windows::named_pipe::ClientOptions::new()
+            .open(format!(r"\\.\pipe\discord-ipc-{}", slot));
+
+        if let Ok(stream) = result {
+            return Ok(stream);
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::NotFound, "未找到正在运行的Discord"))
+}
+
+/// Discord 在类 Unix 系统上创建套接字的目录
+#[cfg(unix)]
+fn socket_dir() -> std::path::PathBuf {
+    ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .iter()
+        .find_map(std::env::var_os)
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| std::path::PathBuf::from("/tmp"))
+}
+
+/// Discord 状态同步任务的句柄
+pub struct DiscordPresence {
+    sender: watch::Sender<Option<Activity>>,
+}
+
+impl DiscordPresence {
+    /// 在当前 Tokio 运行时上启动同步任务
+    ///
+    /// 任务在活动状态变化、收到后台事件或重试间隔到达时检查配置，
+    /// Discord 未运行时按递增的间隔重连，只有第一次失败记录警告。
+    /// 退出时先清除 Discord 上的状态再停止。
+    pub fn spawn(state: AppState) -> Self {
+        let (sender, mut receiver) = watch::channel(None::<Activity>);
+
+        let shutdown = state.shutdown.clone();
+        tokio::spawn(shutdown.track(async move {
+            let mut events = state.events.subscribe();
+            let mut connection: Option<DiscordIpc<IpcStream>> = None;
+            let mut published: Option<Option<Activity>> = None;
+            let mut failures = 0u32;
+            let mut retry_at = Instant::now();
+
+            loop {
+                let config = state.read(|snapshot| snapshot.config.discord.clone());
+                let activity = receiver.borrow_and_update().clone();
+
+                if config.enabled && !config.client_id.is_empty() {
+                    if connection.is_none() && Instant::now() >= retry_at {
+                        let result = match connect().await {
+                            Ok(stream) => DiscordIpc::handshake(stream, &config.client_id).await,
+                            Err(e) => Err(e),
+                        };
+                        match result {
+                            Ok(ipc) => {
+                                info!("已连接到Discord");
+                                connection = Some(ipc);
+                                published = None;
+                                failures = 0;
+                            }
+                            Err(e) => {
+                                if failures == 0 {
+                                    warn!("连接Discord失败: {}", e);
+                                } else {
+                                    debug!("连接Discord失败（第{}次）: {}", failures + 1, e);
+                                }
+                                retry_at = Instant::now() + reconnect_delay(failures);
+                                failures += 1;
+                            }
+                        }
+                    }
+
+                    if let Some(ipc) = connection.as_mut() {
+                        if published.as_ref() != Some(&activity) {
+                            match ipc.set_activity(activity.as_ref()).await {
+                                Ok(_) => published = Some(activity),
+                                Err(e) => {
+                                    // 与连接失败一样按递增的间隔重连
+                                    warn!("更新Discord状态失败: {}", e);
+                                    connection = None;
+                                    retry_at = Instant::now() + reconnect_delay(failures);
+                                    failures += 1;
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    failures = 0;
+                    retry_at = Instant::now();
+                    if let Some(mut ipc) = connection.take() {
+                        // 关闭功能时先清除状态再断开
+                        let _ = ipc.set_activity(None).await;
+                        published = None;
+                        info!("已断开Discord连接");
+                    }
+                }
+
+                let wake_at = match connection {
+                    Some(_) => Instant::now() + RECONNECT_INTERVAL,
+                    None => retry_at.max(Instant::now() + Duration::from_secs(1)),
+                };
+                tokio::select! {
+                    changed = receiver.changed() => {
+                        if changed.is_err() {
+                            break;
+                        }
+                    }
+                    _ = events.recv() => {}
+                    _ = tokio::time::sleep_until(wake_at) => {}
+                    _ = state.shutdown.cancelled() => break,
+                }
+            }
+
+            if let Some(mut ipc) = connection.take() {
+                let _ = ipc.set_activity(None).await;
+                info!("已清除Discord状态");
+            }
+        }));
+
+        Self { sender }
+    }
+
+    /// 更新要展示的活动状态
+    pub fn update(&self, activity: Option<Activity>) {
+        self.sender.send_if_modified(|current| {
+            if *current != activity {
+                *current = activity;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+/// 第 `failures + 1` 次连接失败后的重试间隔
+fn reconnect_delay(failures: u32) -> Duration {
+    RECONNECT_INTERVAL
+        .saturating_mul(1 << failures.min(8))
+        .min(MAX_RECONNECT_INTERVAL)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tokio::net::UnixListener;
+
+    #[test]
+    fn encodes_little_endian_header() {
+        let frame = encode_frame(OP_FRAME, &serde_json::json!({ "a": 1 }));
+        assert_eq!(&frame[..4], &1u32.to_le_bytes());
+        assert_eq!(&frame[4..8], &7u32.to_le_bytes());
+        assert_eq!(&frame[8..], br#"{"a":1}"#);
+    }
+
+    #[tokio::test]
+    async fn handshake_and_set_activity_against_stand_in() {
+        let path = std::env::temp_dir().join(format!("micept-discord-ipc-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let stand_in = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+
+            let (opcode, handshake) = read_frame(&mut stream).await.unwrap();
+            assert_eq!(opcode, OP_HANDSHAKE);
+            assert_eq!(handshake["client_id"], "1234");
+            let ready = encode_frame(OP_FRAME, &serde_json::json!({ "cmd": "DISPATCH", "evt": "READY" }));
+            stream.write_all(&ready).await.unwrap();
+
+            // 先发一次心跳，客户端应当回复后再继续
+            let ping = encode_frame(OP_PING, &serde_json::json!({ "ping": 1 }));
+            let (opcode, command) = read_frame(&mut stream).await.unwrap();
+            stream.write_all(&ping).await.unwrap();
+            let (pong, _) = read_frame(&mut stream).await.unwrap();
+            let reply = encode_frame(
+                OP_FRAME,
+                &serde_json::json!({ "cmd": "SET_ACTIVITY", "evt": null, "nonce": command["nonce"] }),
+            );
+            stream.write_all(&reply).await.unwrap();
+            (opcode, command, pong)
+        });
+
+        let stream = IpcStream::connect(&path).await.unwrap();
+        let mut ipc = DiscordIpc::handshake(stream, "1234").await.unwrap();
+        let activity = Activity::for_phase("InProgress", "单双排位", Some("阿狸"), 1_700_000_000).unwrap();
+        ipc.set_activity(Some(&activity)).await.unwrap();
+
+        let (opcode, command, pong) = stand_in.await.unwrap();
+        assert_eq!(opcode, OP_FRAME);
+        assert_eq!(pong, OP_PONG);
+        assert_eq!(command["cmd"], "SET_ACTIVITY");
+        assert_eq!(command["args"]["activity"]["details"], "游戏中 · 阿狸");
+        assert_eq!(command["args"]["activity"]["state"], "单双排位");
+        assert_eq!(command["args"]["activity"]["timestamps"]["start"], 1_700_000_000);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn silent_peer_times_out() {
+        let (client, _peer) = tokio::io::duplex(1024);
+        let Err(error) = DiscordIpc::handshake(client, "1234").await else {
+            panic!("没有响应时握手应失败");
+        };
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn reconnect_delay_backs_off_to_limit() {
+        assert_eq!(reconnect_delay(0), RECONNECT_INTERVAL);
+        assert_eq!(reconnect_delay(1), RECONNECT_INTERVAL * 2);
+        assert_eq!(reconnect_delay(2), RECONNECT_INTERVAL * 4);
+        assert_eq!(reconnect_delay(20), MAX_RECONNECT_INTERVAL);
+    }
+
+    #[test]
+    fn idle_phases_clear_activity() {
+        assert_eq!(Activity::for_phase("Lobby", "", None, 0), None);
+    }
+}
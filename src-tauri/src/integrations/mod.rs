@@ -0,0 +1,6 @@
+/// 集成模块 - 与外部服务的对接
+pub mod webhook;
+pub mod api;
+pub mod overlay;
+pub mod discord;
+pub mod mqtt;
\ No newline at end of file
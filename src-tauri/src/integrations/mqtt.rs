@@ -0,0 +1,392 @@
+use crate::core::{session::SessionSummary, AppState};
+use crate::lol::SummonerInfo;
+use crate::ui::tray;
+use crate::utils::config::MqttConfig;
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::{broadcast::error::RecvError, mpsc};
+use tracing::{info, warn};
+
+/// 连接断开后的重连间隔
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 客户端请求队列容量
+const REQUEST_CAPACITY: usize = 32;
+
+/// 通过命令主题收到的开关指令
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MqttCommand {
+    AutoAccept(bool),
+    AutoHide(bool),
+}
+
+/// 事件循环转交给发布任务的通知
+enum Notice {
+    Connected,
+    Command(MqttCommand),
+}
+
+/// 拼接带前缀的主题
+pub fn topic(prefix: &str, name: &str) -> String {
+    format!("{}/{}", prefix.trim_end_matches('/'), name)
+}
+
+/// 解析 `mqtt://host:port` 形式的服务器地址，端口缺省为 1883
+pub fn parse_broker_url(url: &str) -> Result<(String, u16), String> {
+    let address = url
+        .strip_prefix("mqtt://")
+        .or_else(|| url.strip_prefix("tcp://"))
+        .ok_or_else(|| format!("不支持的MQTT地址: {}", url))?
+        .trim_end_matches('/');
+
+    match address.rsplit_once(':') {
+        Some((host, port)) => {
+            let port = port.parse().map_err(|_| format!("无效的MQTT端口: {}", port))?;
+            Ok((host.to_string(), port))
+        }
+        None => Ok((address.to_string(), 1883)),
+    }
+}
+
+/// 根据配置生成连接参数
+pub fn mqtt_options(config: &MqttConfig) -> Result<MqttOptions, String> {
+    let (host, port) = parse_broker_url(&config.broker_url)?;
+    let mut options = MqttOptions::new(format!("micept-{}", std::process::id()), host, port);
+    options.set_keep_alive(Duration::from_secs(30));
+    if !config.username.is_empty() {
+        options.set_credentials(config.username.clone(), config.password.clone());
+    }
+    Ok(options)
+}
+
+/// 解析命令主题上的消息，接受 true/false、on/off 和 1/0
+pub fn parse_command(prefix: &str, message_topic: &str, payload: &[u8]) -> Option<MqttCommand> {
+    let enabled = match String::from_utf8_lossy(payload).trim().to_ascii_lowercase().as_str() {
+        "true" | "on" | "1" => true,
+        "false" | "off" | "0" => false,
+        _ => return None,
+    };
+
+    if message_topic == topic(prefix, "auto_accept/set") {
+        Some(MqttCommand::AutoAccept(enabled))
+    } else if message_topic == topic(prefix, "auto_hide/set") {
+        Some(MqttCommand::AutoHide(enabled))
+    } else {
+        None
+    }
+}
+
+/// 生成各状态主题的内容
+///
+/// 召唤师主题是保留消息，只包含展示用的字段，不发布 puuid 等账号标识。
+pub fn state_messages(
+    prefix: &str,
+    phase: &str,
+    summoner: Option<&SummonerInfo>,
+    session: &SessionSummary,
+) -> Vec<(String, String)> {
+    let summoner = summoner.map(|summoner| {
+        serde_json::json!({
+            "display_name": summoner.display_name,
+            "summoner_level": summoner.summoner_level,
+            "profile_icon_id": summoner.profile_icon_id,
+        })
+    });
+    vec![
+        (topic(prefix, "phase"), phase.to_string()),
+        (topic(prefix, "summoner"), serde_json::to_string(&summoner).unwrap_or_default()),
+        (topic(prefix, "ready_check"), (phase == "ReadyCheck").to_string()),
+        (topic(prefix, "session"), serde_json::to_string(session).unwrap_or_default()),
+    ]
+}
+
+/// 以保留消息发布状态，只发送内容有变化的主题
+pub struct StatePublisher {
+    client: AsyncClient,
+    prefix: String,
+    published: HashMap<String, String>,
+}
+
+impl StatePublisher {
+    pub fn new(client: AsyncClient, prefix: &str) -> Self {
+        Self {
+            client,
+            prefix: prefix.to_string(),
+            published: HashMap::new(),
+        }
+    }
+
+    /// 连接建立后订阅命令主题，并在下次发布时重新发送全部状态
+    pub fn on_connected(&mut self) {
+        self.published.clear();
+        for name in ["auto_accept/set", "auto_hide/set"] {
+            if let Err(e) = self.client.try_subscribe(topic(&self.prefix, name), QoS::AtLeastOnce) {
+                warn!("订阅MQTT命令主题失败: {}", e);
+            }
+        }
+    }
+
+    /// 发布有变化的状态
+    pub fn publish_changed(&mut self, messages: Vec<(String, String)>) {
+        for (message_topic, payload) in messages {
+            if self.published.get(&message_topic) == Some(&payload) {
+                continue;
+            }
+            match self
+                .client
+                .try_publish(message_topic.clone(), QoS::AtLeastOnce, true, payload.clone())
+            {
+                Ok(_) => {
+                    self.published.insert(message_topic, payload);
+                }
+                Err(e) => warn!("发布MQTT消息失败: {}", e),
+            }
+        }
+    }
+}
+
+/// 当前状态对应的消息
+fn snapshot_messages(prefix: &str, state: &AppState) -> Vec<(String, String)> {
+    let (phase, summoner, session) = state.read(|snapshot| {
+        (
+            snapshot.gameflow_phase.clone(),
+            snapshot.summoner_info.clone(),
+            snapshot.session.summary(),
+        )
+    });
+    state_messages(prefix, &phase, summoner.as_ref(), &session)
+}
+
+/// 执行命令主题上收到的指令
+fn apply_command(state: &AppState, command: MqttCommand) {
+    info!("收到MQTT指令: {:?}", command);
+    match command {
+        MqttCommand::AutoAccept(enabled) => tray::set_auto_accept(state, enabled),
+        MqttCommand::AutoHide(enabled) => tray::set_auto_hide(state, enabled),
+    }
+}
+
+/// 启动 MQTT 客户端
+///
+/// 事件循环单独运行并负责断线重连，状态在每次后台事件后发布，应用退出时两个任务都会结束。
+pub fn start(state: AppState, config: MqttConfig) {
+    let options = match mqtt_options(&config) {
+        Ok(options) => options,
+        Err(e) => {
+            warn!("MQTT启动失败: {}", e);
+            return;
+        }
+    };
+    let (client, mut eventloop) = AsyncClient::new(options, REQUEST_CAPACITY);
+    let (notices, mut notice_receiver) = mpsc::unbounded_channel();
+    let prefix = config.topic_prefix;
+
+    let command_prefix = prefix.clone();
+    let shutdown = state.shutdown.clone();
+    tauri::async_runtime::spawn(shutdown.clone().track(async move {
+        loop {
+            let polled = tokio::select! {
+                _ = shutdown.cancelled() => return,
+                polled = eventloop.poll() => polled,
+            };
+            let notice = match polled {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    info!("已连接到MQTT服务器");
+                    Some(Notice::Connected)
+                }
+                Ok(Event::Incoming(Packet::Publish(message))) => {
+                    parse_command(&command_prefix, &message.topic, &message.payload).map(Notice::Command)
+                }
+                Ok(_) => None,
+                Err(e) => {
+                    warn!("MQTT连接异常: {}", e);
+                    tokio::select! {
+                        _ = shutdown.cancelled() => return,
+                        _ = tokio::time::sleep(RECONNECT_INTERVAL) => None,
+                    }
+                }
+            };
+            if let Some(notice) = notice {
+                if notices.send(notice).is_err() {
+                    return;
+                }
+            }
+        }
+    }));
+
+    let shutdown = state.shutdown.clone();
+    tauri::async_runtime::spawn(shutdown.track(async move {
+        let mut publisher = StatePublisher::new(client, &prefix);
+        let mut events = state.events.subscribe();
+
+        loop {
+            tokio::select! {
+                _ = state.shutdown.cancelled() => return,
+                notice = notice_receiver.recv() => match notice {
+                    Some(Notice::Connected) => publisher.on_connected(),
+                    Some(Notice::Command(command)) => apply_command(&state, command),
+                    None => return,
+                },
+                event = events.recv() => {
+                    if let Err(RecvError::Closed) = event {
+                        return;
+                    }
+                }
+            }
+            publisher.publish_changed(snapshot_messages(&prefix, &state));
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::mpsc as std_mpsc;
+
+    /// 读取一个 MQTT 控制报文，返回首字节和报文体
+    fn read_packet(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+        let mut byte = [0u8; 1];
+        stream.read_exact(&mut byte).unwrap();
+        let header = byte[0];
+
+        let mut length = 0usize;
+        let mut shift = 0;
+        loop {
+            stream.read_exact(&mut byte).unwrap();
+            length |= ((byte[0] & 0x7f) as usize) << shift;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+
+        let mut body = vec![0u8; length];
+        stream.read_exact(&mut body).unwrap();
+        (header, body)
+    }
+
+    /// 本地 MQTT 服务器替身：完成握手、确认订阅，记录收到的发布后下发一条命令
+    fn stand_in(expected_publishes: usize) -> (u16, std_mpsc::Receiver<(String, String, bool)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (sender, receiver) = std_mpsc::channel();
+
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let (header, _) = read_packet(&mut stream);
+            assert_eq!(header >> 4, 1, "首个报文应为 CONNECT");
+            stream.write_all(&[0x20, 0x02, 0x00, 0x00]).unwrap();
+
+            let mut publishes = 0;
+            while publishes < expected_publishes {
+                let (header, body) = read_packet(&mut stream);
+                match header >> 4 {
+                    // SUBSCRIBE
+                    8 => stream.write_all(&[0x90, 0x03, body[0], body[1], 0x01]).unwrap(),
+                    // PUBLISH
+                    3 => {
+                        let topic_len = u16::from_be_bytes([body[0], body[1]]) as usize;
+                        let topic = String::from_utf8(body[2..2 + topic_len].to_vec()).unwrap();
+                        let mut offset = 2 + topic_len;
+                        if (header >> 1) & 0x03 > 0 {
+                            stream.write_all(&[0x40, 0x02, body[offset], body[offset + 1]]).unwrap();
+                            offset += 2;
+                        }
+                        let payload = String::from_utf8(body[offset..].to_vec()).unwrap();
+                        sender.send((topic, payload, header & 0x01 == 1)).unwrap();
+                        publishes += 1;
+                    }
+                    _ => {}
+                }
+            }
+
+            // 下发关闭自动接受的命令（QoS 0）
+            let topic = b"micept/auto_accept/set";
+            let mut packet = vec![0x30, (2 + topic.len() + 3) as u8, 0x00, topic.len() as u8];
+            packet.extend_from_slice(topic);
+            packet.extend_from_slice(b"off");
+            stream.write_all(&packet).unwrap();
+
+            // 保持连接直到客户端断开
+            let mut buffer = [0u8; 64];
+            while matches!(stream.read(&mut buffer), Ok(read) if read > 0) {}
+        });
+
+        (port, receiver)
+    }
+
+    #[test]
+    fn parses_broker_url_and_commands() {
+        assert_eq!(parse_broker_url("mqtt://broker.lan").unwrap(), ("broker.lan".to_string(), 1883));
+        assert_eq!(parse_broker_url("tcp://10.0.0.2:1884/").unwrap(), ("10.0.0.2".to_string(), 1884));
+        assert!(parse_broker_url("mqtts://broker.lan").is_err());
+
+        assert_eq!(
+            parse_command("home/micept/", "home/micept/auto_hide/set", b" ON "),
+            Some(MqttCommand::AutoHide(true))
+        );
+        assert_eq!(parse_command("micept", "micept/auto_accept/set", b"maybe"), None);
+        assert_eq!(parse_command("micept", "micept/phase", b"true"), None);
+    }
+
+    #[test]
+    fn summoner_topic_omits_account_identifiers() {
+        let summoner = SummonerInfo {
+            puuid: "secret-puuid".to_string(),
+            display_name: "Tester".to_string(),
+            summoner_level: 30,
+            profile_icon_id: 7,
+            xp_since_last_level: 0,
+            xp_until_next_level: 100,
+        };
+        let messages = state_messages("micept", "Lobby", Some(&summoner), &SessionSummary::default());
+        let (_, payload) = messages.iter().find(|(name, _)| name == "micept/summoner").unwrap();
+        assert!(!payload.contains("secret-puuid"), "{}", payload);
+        let payload: serde_json::Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({"display_name": "Tester", "summoner_level": 30, "profile_icon_id": 7})
+        );
+    }
+
+    #[tokio::test]
+    async fn publishes_retained_state_and_receives_commands() {
+        let (port, received) = stand_in(4);
+        let options = mqtt_options(&MqttConfig {
+            broker_url: format!("mqtt://127.0.0.1:{}", port),
+            ..Default::default()
+        })
+        .unwrap();
+        let (client, mut eventloop) = AsyncClient::new(options, REQUEST_CAPACITY);
+        let mut publisher = StatePublisher::new(client, "micept");
+
+        let messages = state_messages("micept", "ReadyCheck", None, &SessionSummary::default());
+        let command = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                match eventloop.poll().await.unwrap() {
+                    Event::Incoming(Packet::ConnAck(_)) => {
+                        publisher.on_connected();
+                        publisher.publish_changed(messages.clone());
+                    }
+                    Event::Incoming(Packet::Publish(message)) => {
+                        break parse_command("micept", &message.topic, &message.payload);
+                    }
+                    _ => {}
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(command, Some(MqttCommand::AutoAccept(false)));
+        let publishes: Vec<_> = received.try_iter().collect();
+        assert_eq!(publishes.len(), 4);
+        assert!(publishes.iter().all(|(_, _, retain)| *retain));
+        assert!(publishes.contains(&("micept/phase".to_string(), "ReadyCheck".to_string(), true)));
+        assert!(publishes.contains(&("micept/ready_check".to_string(), "true".to_string(), true)));
+        assert!(publishes.contains(&("micept/summoner".to_string(), "null".to_string(), true)));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::core::AppState;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::{header::ORIGIN, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use std::net::{Ipv4Addr, SocketAddr};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+/// OBS 浏览器源加载的页面
+const OVERLAY_HTML: &str = include_str!("overlay.html");
+
+/// 启动 OBS 叠加层服务，只监听 127.0.0.1
+///
+/// 服务只依赖 `AppState`，主窗口隐藏时同样可用，应用退出时关闭。
+pub fn start(state: AppState, port: u16) {
+    let shutdown = state.shutdown.clone();
+    tauri::async_runtime::spawn(shutdown.clone().track(async move {
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let listener = match tokio::net::TcpListener::bind(address).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("叠加层服务监听端口{}失败: {}", port, e);
+                return;
+            }
+        };
+        info!("叠加层服务已启动: http://{}", address);
+
+        let router = Router::new()
+            .route("/", get(page))
+            .route("/ws", get(feed))
+            .with_state(Overlay { state, port });
+        let stopped = async move { shutdown.cancelled().await };
+        if let Err(e) = axum::serve(listener, router).with_graceful_shutdown(stopped).await {
+            warn!("叠加层服务异常退出: {}", e);
+        }
+    }));
+}
+
+#[derive(Clone)]
+struct Overlay {
+    state: AppState,
+    port: u16,
+}
+
+/// 叠加层需要展示的状态快照，不包含 puuid 等账号标识
+fn overlay_snapshot(state: &AppState) -> serde_json::Value {
+    let (summoner_info, player_profile, gameflow_phase, session_summary, phase_elapsed_seconds) = state.read(|snapshot| {
+        (
+            snapshot.summoner_info.clone(),
+            snapshot.player_profile.clone(),
+            snapshot.gameflow_phase.clone(),
+            snapshot.session.summary(),
+            snapshot.session.phase_elapsed_seconds(),
+        )
+    });
+
+    let summoner_info = summoner_info.map(|summoner| {
+        serde_json::json!({
+            "display_name": summoner.display_name,
+            "summoner_level": summoner.summoner_level,
+            "profile_icon_id": summoner.profile_icon_id,
+        })
+    });
+
+    serde_json::json!({
+        "summoner_info": summoner_info,
+        "player_profile": player_profile,
+        "gameflow_phase": gameflow_phase,
+        "phase_elapsed_seconds": phase_elapsed_seconds,
+        "session_summary": session_summary
+    })
+}
+
+async fn page() -> Html<&'static str> {
+    Html(OVERLAY_HTML)
+}
+
+/// 只允许叠加层页面自身或没有 `Origin` 的客户端（如 OBS 浏览器源）连接，
+/// 防止浏览器中打开的其它网页读取召唤师信息
+fn origin_allowed(headers: &HeaderMap, port: u16) -> bool {
+    match headers.get(ORIGIN) {
+        None => true,
+        Some(origin) => {
+            let origin = origin.to_str().unwrap_or_default();
+            origin == format!("http://127.0.0.1:{}", port) || origin == format!("http://localhost:{}", port)
+        }
+    }
+}
+
+async fn feed(upgrade: WebSocketUpgrade, headers: HeaderMap, State(overlay): State<Overlay>) -> Response {
+    if !origin_allowed(&headers, overlay.port) {
+        warn!("拒绝来源不明的叠加层连接: {:?}", headers.get(ORIGIN));
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    upgrade.on_upgrade(move |socket| push_snapshots(socket, overlay.state))
+}
+
+/// 连接后先发送一次快照，之后每当后台发出事件时推送最新快照，应用退出时断开
+async fn push_snapshots(mut socket: WebSocket, state: AppState) {
+    let mut events = state.events.subscribe();
+
+    loop {
+        let message = Message::Text(overlay_snapshot(&state).to_string().into());
+        if socket.send(message).await.is_err() {
+            return;
+        }
+
+        tokio::select! {
+            _ = state.shutdown.cancelled() => return,
+            event = events.recv() => {
+                if let Err(RecvError::Closed) = event {
+                    return;
+                }
+            }
+            incoming = socket.recv() => {
+                // 页面只接收数据，连接关闭或出错时退出
+                match incoming {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(origin: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(origin) = origin {
+            headers.insert(ORIGIN, origin.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn only_own_origin_or_missing_origin_is_allowed() {
+        assert!(origin_allowed(&headers(None), 17322));
+        assert!(origin_allowed(&headers(Some("http://127.0.0.1:17322")), 17322));
+        assert!(origin_allowed(&headers(Some("http://localhost:17322")), 17322));
+        assert!(!origin_allowed(&headers(Some("https://example.com")), 17322));
+        assert!(!origin_allowed(&headers(Some("http://127.0.0.1:8080")), 17322));
+        assert!(!origin_allowed(&headers(Some("null")), 17322));
+    }
+}
@@ -0,0 +1,274 @@
+use crate::core::shutdown::Shutdown;
+use crate::utils::config::WebhookConfig;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// 待发送队列的容量，队列已满时丢弃新的事件
+const QUEUE_CAPACITY: usize = 64;
+
+/// 首次重试前的等待时间，之后每次翻倍
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// 会触发 Webhook 的游戏事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    ReadyCheckAccepted,
+    GameStart,
+    GameEnd,
+}
+
+impl WebhookEvent {
+    /// 事件名称，同时作为请求体中的 `event` 字段
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebhookEvent::ReadyCheckAccepted => "ready_check_accepted",
+            WebhookEvent::GameStart => "game_start",
+            WebhookEvent::GameEnd => "game_end",
+        }
+    }
+
+    /// 该事件配置的地址
+    fn urls<'a>(&self, config: &'a WebhookConfig) -> &'a [String] {
+        match self {
+            WebhookEvent::ReadyCheckAccepted => &config.ready_check_accepted,
+            WebhookEvent::GameStart => &config.game_start,
+            WebhookEvent::GameEnd => &config.game_end,
+        }
+    }
+}
+
+/// 一次待发送的请求
+#[derive(Debug, Clone)]
+struct WebhookJob {
+    url: String,
+    event: &'static str,
+    body: String,
+    signature: Option<String>,
+    timeout: Duration,
+    max_retries: u32,
+}
+
+/// Webhook 分发器
+///
+/// 事件通过有界队列交给后台的发送任务，入队不会等待网络请求，
+/// 因此不会阻塞后台任务的主循环。退出时队列中剩余的事件只尝试发送一次。
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    sender: mpsc::Sender<WebhookJob>,
+}
+
+impl WebhookDispatcher {
+    /// 在当前 Tokio 运行时上启动发送任务
+    pub fn spawn(shutdown: &Shutdown) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<WebhookJob>(QUEUE_CAPACITY);
+        let shutdown = shutdown.clone();
+        tokio::spawn(shutdown.clone().track(async move {
+            let client = reqwest::Client::new();
+            loop {
+                let job = tokio::select! {
+                    job = receiver.recv() => job,
+                    _ = shutdown.cancelled() => {
+                        // 不再接受新的事件，继续发送已入队的事件
+                        receiver.close();
+                        receiver.recv().await
+                    }
+                };
+                let Some(job) = job else {
+                    break;
+                };
+                if let Err(e) = deliver(&client, &job, &shutdown).await {
+                    warn!("Webhook发送失败 {} -> {}: {}", job.event, job.url, e);
+                }
+            }
+        }));
+        Self { sender }
+    }
+
+    /// 将事件加入发送队列，返回成功入队的请求数量
+    pub fn dispatch(&self, event: WebhookEvent, data: serde_json::Value, config: &WebhookConfig) -> usize {
+        let urls = event.urls(config);
+        if urls.is_empty() {
+            return 0;
+        }
+
+        let body = serde_json::json!({
+            "event": event.name(),
+            "timestamp": chrono::Local::now().to_rfc3339(),
+            "data": data,
+        })
+        .to_string();
+        let signature = config
+            .secret
+            .as_deref()
+            .filter(|secret| !secret.is_empty())
+            .map(|secret| sign(secret, &body));
+
+        let mut queued = 0;
+        for url in urls {
+            let job = WebhookJob {
+                url: url.clone(),
+                event: event.name(),
+                body: body.clone(),
+                signature: signature.clone(),
+                timeout: Duration::from_secs(config.timeout_secs.max(1)),
+                max_retries: config.max_retries,
+            };
+            match self.sender.try_send(job) {
+                Ok(_) => queued += 1,
+                Err(e) => warn!("Webhook队列已满或已关闭，丢弃事件 {}: {}", event.name(), e),
+            }
+        }
+        queued
+    }
+}
+
+/// 计算请求体的 HMAC-SHA256 签名，格式为 `sha256=<十六进制>`
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC可以接受任意长度的密钥");
+    mac.update(body.as_bytes());
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("sha256={}", hex)
+}
+
+/// 发送一次请求，失败时按指数退避重试，退出时不再重试
+async fn deliver(client: &reqwest::Client, job: &WebhookJob, shutdown: &Shutdown) -> Result<(), String> {
+    let mut attempt = 0;
+    loop {
+        let mut request = client
+            .post(&job.url)
+            .timeout(job.timeout)
+            .header("Content-Type", "application/json")
+            .header("X-Micept-Event", job.event)
+            .body(job.body.clone());
+        if let Some(signature) = &job.signature {
+            request = request.header("X-Micept-Signature", signature);
+        }
+
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => format!("状态码: {}", response.status()),
+            Err(e) => format!("请求失败: {}", e),
+        };
+
+        if attempt >= job.max_retries || shutdown.is_cancelled() {
+            return Err(error);
+        }
+        let delay = RETRY_BASE_DELAY * 2u32.pow(attempt);
+        warn!("Webhook发送失败({})，{:?}后重试: {}", error, delay, job.url);
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown.cancelled() => return Err(error),
+        }
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc as std_mpsc;
+
+    /// 本地 HTTP 替身，按顺序返回给定的状态码，并把收到的请求原文发回测试
+    fn stand_in(statuses: Vec<u16>) -> (String, std_mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (sender, receiver) = std_mpsc::channel();
+
+        std::thread::spawn(move || {
+            for status in statuses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+                loop {
+                    let read = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some(header_end) = text.find("\r\n\r\n") {
+                        let content_length = text[..header_end]
+                            .lines()
+                            .find_map(|line| {
+                                let (name, value) = line.split_once(':')?;
+                                name.eq_ignore_ascii_case("content-length")
+                                    .then(|| value.trim().parse::<usize>().ok())?
+                            })
+                            .unwrap_or(0);
+                        if request.len() >= header_end + 4 + content_length {
+                            break;
+                        }
+                    }
+                    if read == 0 {
+                        break;
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 {} Stand-In\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+                sender.send(String::from_utf8_lossy(&request).to_string()).unwrap();
+            }
+        });
+
+        (url, receiver)
+    }
+
+    fn config(url: &str) -> WebhookConfig {
+        WebhookConfig {
+            game_end: vec![url.to_string()],
+            secret: Some("team-secret".to_string()),
+            timeout_secs: 2,
+            max_retries: 1,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn delivers_signed_payload_and_retries_on_failure() {
+        let (url, requests) = stand_in(vec![500, 200]);
+        let dispatcher = WebhookDispatcher::spawn(&Shutdown::default());
+
+        let queued = dispatcher.dispatch(
+            WebhookEvent::GameEnd,
+            serde_json::json!({ "win": true }),
+            &config(&url),
+        );
+        assert_eq!(queued, 1);
+
+        let (failed, retried) = tokio::task::spawn_blocking(move || {
+            let failed = requests.recv_timeout(Duration::from_secs(5)).unwrap();
+            let retried = requests.recv_timeout(Duration::from_secs(5)).unwrap();
+            (failed, retried)
+        })
+        .await
+        .unwrap();
+        assert_eq!(failed, retried);
+
+        let body = &retried[retried.find("\r\n\r\n").unwrap() + 4..];
+        let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(payload["event"], "game_end");
+        assert_eq!(payload["data"]["win"], true);
+
+        let signature = sign("team-secret", body);
+        assert!(retried.to_lowercase().contains(&format!("x-micept-signature: {}", signature)));
+    }
+
+    #[test]
+    fn events_without_urls_are_not_queued() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let dispatcher = runtime.block_on(async { WebhookDispatcher::spawn(&Shutdown::default()) });
+
+        let queued = dispatcher.dispatch(
+            WebhookEvent::GameStart,
+            serde_json::Value::Null,
+            &config("http://127.0.0.1:9/hook"),
+        );
+        assert_eq!(queued, 0);
+    }
+}
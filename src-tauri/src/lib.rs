@@ -2,11 +2,16 @@
 
 // 模块声明
 mod app;
+mod headless;
 mod core;
 mod ui;
 mod lol;
 mod utils;
+mod integrations;
 mod commands;
+pub mod cli;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 // 重新导出主要的运行函数
 pub use app::run;
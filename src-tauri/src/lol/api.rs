@@ -0,0 +1,77 @@
+use super::{
+    accept_match, fetch_champion_names, get_champ_select_session, get_end_of_game_stats, get_gameflow_phase,
+    get_lcu_auth, get_player_profile, get_ranked_stats, get_summoner_info, validate_lcu_connection,
+    ChampSelectSession, EndOfGameStats, GameflowSession, LcuAuthInfo, PlayerProfile, RankedStats, SummonerInfo,
+};
+use futures_util::future::BoxFuture;
+use std::collections::HashMap;
+
+/// 客户端接口返回的 Future
+pub type LcuFuture<'a, T> = BoxFuture<'a, Result<T, String>>;
+
+/// 后台任务访问英雄联盟客户端的接口
+///
+/// `LiveLcu` 调用正在运行的客户端，测试中使用内存中的替身。
+pub trait LcuApi: Send + Sync {
+    /// 查找客户端进程并读取端口和令牌
+    fn discover(&self) -> LcuFuture<'_, LcuAuthInfo>;
+    fn validate(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, ()>;
+    fn summoner_info(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, SummonerInfo>;
+    fn player_profile(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, PlayerProfile>;
+    fn ranked_stats(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, RankedStats>;
+    fn gameflow_session(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, GameflowSession>;
+    fn champ_select_session(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, ChampSelectSession>;
+    fn end_of_game_stats(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, EndOfGameStats>;
+    /// 英雄ID到名称的映射，获取失败时为空
+    fn champion_names(&self, auth: &LcuAuthInfo) -> BoxFuture<'_, HashMap<u32, String>>;
+    fn accept_match(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, ()>;
+}
+
+/// 通过 LCU 接口访问正在运行的客户端
+pub struct LiveLcu;
+
+impl LcuApi for LiveLcu {
+    fn discover(&self) -> LcuFuture<'_, LcuAuthInfo> {
+        Box::pin(get_lcu_auth())
+    }
+
+    fn validate(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, ()> {
+        Box::pin(validate_lcu_connection(auth.port.clone(), auth.token.clone()))
+    }
+
+    fn summoner_info(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, SummonerInfo> {
+        Box::pin(get_summoner_info(auth.port.clone(), auth.token.clone()))
+    }
+
+    fn player_profile(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, PlayerProfile> {
+        Box::pin(get_player_profile(auth.port.clone(), auth.token.clone()))
+    }
+
+    fn ranked_stats(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, RankedStats> {
+        Box::pin(get_ranked_stats(auth.port.clone(), auth.token.clone()))
+    }
+
+    fn gameflow_session(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, GameflowSession> {
+        Box::pin(get_gameflow_phase(auth.port.clone(), auth.token.clone()))
+    }
+
+    fn champ_select_session(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, ChampSelectSession> {
+        let auth = auth.clone();
+        Box::pin(async move { get_champ_select_session(&auth.port, &auth.token).await })
+    }
+
+    fn end_of_game_stats(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, EndOfGameStats> {
+        let auth = auth.clone();
+        Box::pin(async move { get_end_of_game_stats(&auth.port, &auth.token).await })
+    }
+
+    fn champion_names(&self, auth: &LcuAuthInfo) -> BoxFuture<'_, HashMap<u32, String>> {
+        let auth = auth.clone();
+        Box::pin(async move { fetch_champion_names(&auth.port, &auth.token).await })
+    }
+
+    fn accept_match(&self, auth: &LcuAuthInfo) -> LcuFuture<'_, ()> {
+        let auth = auth.clone();
+        Box::pin(async move { accept_match(auth.port, auth.token).await.map(|_| ()) })
+    }
+}
@@ -0,0 +1,56 @@
+use serde::Deserialize;
+use super::client::lcu_get;
+
+/// 英雄选择中的一次操作（禁用或选择）
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChampSelectAction {
+    pub actor_cell_id: i64,
+    #[serde(rename = "type")]
+    pub action_type: String,
+    pub is_in_progress: bool,
+    pub completed: bool,
+}
+
+/// 英雄选择中己方队伍的一名玩家
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChampSelectPlayer {
+    pub cell_id: i64,
+    pub champion_id: u32,
+}
+
+/// `/lol-champ-select/v1/session` 的返回结果
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChampSelectSession {
+    pub local_player_cell_id: i64,
+    pub actions: Vec<Vec<ChampSelectAction>>,
+    pub my_team: Vec<ChampSelectPlayer>,
+}
+
+impl ChampSelectSession {
+    /// 当前是否轮到本地玩家选择英雄
+    pub fn is_local_pick_turn(&self) -> bool {
+        self.actions.iter().flatten().any(|action| {
+            action.actor_cell_id == self.local_player_cell_id
+                && action.action_type == "pick"
+                && action.is_in_progress
+                && !action.completed
+        })
+    }
+
+    /// 本地玩家当前选择的英雄，尚未选择时返回 None
+    pub fn local_champion_id(&self) -> Option<u32> {
+        self.my_team
+            .iter()
+            .find(|player| player.cell_id == self.local_player_cell_id)
+            .map(|player| player.champion_id)
+            .filter(|champion_id| *champion_id > 0)
+    }
+}
+
+/// 获取英雄选择会话
+pub async fn get_champ_select_session(port: &str, token: &str) -> Result<ChampSelectSession, String> {
+    lcu_get(port, token, "/lol-champ-select/v1/session").await
+}
@@ -1,5 +1,6 @@
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
 use base64::{Engine as _, engine::general_purpose};
+use crate::utils::redact;
 
 #[cfg(not(target_os = "windows"))]
 use std::process::Command;
@@ -10,6 +11,9 @@ use std::process::{Command as WinCommand, Stdio};
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
+/// 客户端没有返回召唤师名称时显示的名称
+pub const UNKNOWN_SUMMONER_NAME: &str = "未知";
+
 #[derive(Serialize, Clone)]
 pub struct LcuAuthInfo {
     pub port: String,
@@ -19,6 +23,7 @@ pub struct LcuAuthInfo {
 
 #[derive(Serialize, Clone)]
 pub struct SummonerInfo {
+    pub puuid: String,
     pub display_name: String,
     pub summoner_level: u32,
     pub profile_icon_id: u32,
@@ -29,6 +34,7 @@ pub struct SummonerInfo {
 #[derive(Serialize)]
 pub struct GameflowSession {
     pub phase: String,
+    pub queue_name: String,
 }
 
 #[derive(Serialize)]
@@ -63,6 +69,35 @@ pub async fn validate_lcu_connection(port: String, token: String) -> Result<(),
     }
 }
 
+/// 向LCU发送GET请求并将响应解析为指定类型
+pub(crate) async fn lcu_get<T: DeserializeOwned>(port: &str, token: &str, path: &str) -> Result<T, String> {
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+    
+    let url = format!("https://127.0.0.1:{}{}", port, path);
+    let auth = format!("riot:{}", token);
+    let auth_header = format!("Basic {}", general_purpose::STANDARD.encode(auth));
+    
+    let response = client
+        .get(&url)
+        .header("Authorization", auth_header)
+        .send()
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+    
+    if response.status().is_success() {
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| format!("解析JSON失败: {}", e))
+    } else {
+        Err(format!("请求{}失败: {}", path, response.status()))
+    }
+}
+
 #[tauri::command]
 pub async fn check_admin_privileges() -> Result<AdminStatus, String> {
     #[cfg(target_os = "windows")]
@@ -105,13 +140,19 @@ pub async fn check_admin_privileges() -> Result<AdminStatus, String> {
     }
 }
 
+/// 登记令牌及其 Basic 认证形式，日志中不会出现
+fn register_token(token: &str) {
+    redact::register(token);
+    redact::register(&general_purpose::STANDARD.encode(format!("riot:{}", token)));
+}
+
 #[tauri::command]
 pub async fn get_lcu_auth() -> Result<LcuAuthInfo, String> {
     #[cfg(target_os = "windows")]
     {
         // Windows平台使用tasklist命令获取进程信息，然后使用wmic获取命令行
         let check_output = WinCommand::new("tasklist")
-            .args(&["/FI", "IMAGENAME eq LeagueClientUx.exe"])
+            .args(["/FI", "IMAGENAME eq LeagueClientUx.exe"])
             .creation_flags(0x08000000) // CREATE_NO_WINDOW
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -119,7 +160,7 @@ pub async fn get_lcu_auth() -> Result<LcuAuthInfo, String> {
             .map_err(|e| format!("执行tasklist命令失败: {}", e))?;
         
         let check_str = String::from_utf8_lossy(&check_output.stdout);
-        eprintln!("tasklist输出: {}", check_str);
+        tracing::debug!("tasklist输出: {}", check_str);
         
         if !check_str.contains("LeagueClientUx.exe") {
             return Err("未找到英雄联盟客户端进程".to_string());
@@ -127,7 +168,7 @@ pub async fn get_lcu_auth() -> Result<LcuAuthInfo, String> {
         
         // 使用PowerShell的Get-WmiObject命令获取命令行参数
         let output = WinCommand::new("powershell")
-            .args(&["-Command", "Get-WmiObject -Class Win32_Process -Filter \"Name='LeagueClientUx.exe'\" | Select-Object -ExpandProperty CommandLine"])
+            .args(["-Command", "Get-WmiObject -Class Win32_Process -Filter \"Name='LeagueClientUx.exe'\" | Select-Object -ExpandProperty CommandLine"])
             .creation_flags(0x08000000) // CREATE_NO_WINDOW
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -135,25 +176,25 @@ pub async fn get_lcu_auth() -> Result<LcuAuthInfo, String> {
             .map_err(|e| format!("执行PowerShell命令失败: {}", e))?;
         
         let output_str = String::from_utf8_lossy(&output.stdout);
-        eprintln!("PowerShell输出: {}", output_str);
+        tracing::debug!("PowerShell输出: {}", output_str);
         
         // PowerShell输出的是直接的命令行字符串
         let command_line = output_str.trim();
         if !command_line.is_empty() && command_line.contains("LeagueClientUx.exe") {
-            eprintln!("找到命令行: {}", command_line);
+            tracing::debug!("找到命令行: {}", command_line);
             
             if command_line.contains("--app-port=") && command_line.contains("--remoting-auth-token=") {
                 let mut port = String::new();
                 let mut token = String::new();
                 
-                eprintln!("开始解析命令行参数...");
+                tracing::debug!("开始解析命令行参数...");
                 
                 // 解析端口
                 if let Some(port_match) = command_line.find("--app-port=") {
                     let port_start = port_match + "--app-port=".len();
                     let port_end = command_line[port_start..].find(' ').unwrap_or(command_line.len() - port_start);
                     port = command_line[port_start..port_start + port_end].trim_matches('"').to_string();
-                    eprintln!("找到端口: '{}'", port);
+                    tracing::debug!("找到端口: '{}'", port);
                 }
                 
                 // 解析token
@@ -161,18 +202,18 @@ pub async fn get_lcu_auth() -> Result<LcuAuthInfo, String> {
                     let token_start = token_match + "--remoting-auth-token=".len();
                     let token_end = command_line[token_start..].find(' ').unwrap_or(command_line.len() - token_start);
                     token = command_line[token_start..token_start + token_end].trim_matches('"').to_string();
-                    eprintln!("找到token: '{}...'", &token[..8.min(token.len())]);
                 }
                 
                 if !port.is_empty() && !token.is_empty() {
-                    eprintln!("成功解析LCU认证信息: port={}, token={}...", port, &token[..8.min(token.len())]);
+                    tracing::debug!("成功解析LCU认证信息: port={}", port);
+                    register_token(&token);
                     return Ok(LcuAuthInfo {
                         port,
                         token,
                         is_connected: true,
                     });
                 } else {
-                    eprintln!("解析失败: port={}, token={}", port, token);
+                    tracing::warn!("解析失败: port={}, token为空: {}", port, token.is_empty());
                 }
             }
         }
@@ -184,7 +225,7 @@ pub async fn get_lcu_auth() -> Result<LcuAuthInfo, String> {
     {
         // 非Windows平台使用ps命令
         let output = Command::new("ps")
-            .args(&["aux"])
+            .args(["aux"])
             .output()
             .map_err(|e| format!("执行ps命令失败: {}", e))?;
         
@@ -206,6 +247,7 @@ pub async fn get_lcu_auth() -> Result<LcuAuthInfo, String> {
                 }
                 
                 if !port.is_empty() && !token.is_empty() {
+                    register_token(&token);
                     return Ok(LcuAuthInfo {
                         port,
                         token,
@@ -244,26 +286,28 @@ pub async fn get_summoner_info(port: String, token: String) -> Result<SummonerIn
             .map_err(|e| format!("解析JSON失败: {}", e))?;
         
         // 优先使用 gameName，如果为空则使用 displayName
-        let display_name = if let Some(game_name) = summoner["gameName"].as_str() {
-            if !game_name.is_empty() {
-                // 如果有 tagLine，则组合显示
-                if let Some(tag_line) = summoner["tagLine"].as_str() {
-                    if !tag_line.is_empty() {
-                        format!("{}#{}", game_name, tag_line)
-                    } else {
-                        game_name.to_string()
-                    }
-                } else {
-                    game_name.to_string()
-                }
-            } else {
-                summoner["displayName"].as_str().unwrap_or("未知").to_string()
-            }
-        } else {
-            summoner["displayName"].as_str().unwrap_or("未知").to_string()
+        let game_name = summoner["gameName"].as_str().unwrap_or_default();
+        let tag_line = summoner["tagLine"].as_str().unwrap_or_default();
+        let legacy_name = summoner["displayName"].as_str().unwrap_or_default();
+        let display_name = match (game_name, tag_line) {
+            ("", _) if !legacy_name.is_empty() => legacy_name.to_string(),
+            ("", _) => UNKNOWN_SUMMONER_NAME.to_string(),
+            // 如果有 tagLine，则组合显示
+            (game_name, "") => game_name.to_string(),
+            (game_name, tag_line) => format!("{}#{}", game_name, tag_line),
         };
 
+        // 只登记客户端返回的名称，不登记占位名称
+        let puuid = summoner["puuid"].as_str().unwrap_or_default().to_string();
+        redact::register(&puuid);
+        if display_name != UNKNOWN_SUMMONER_NAME {
+            redact::register(&display_name);
+        }
+        redact::register(game_name);
+        redact::register(legacy_name);
+
         Ok(SummonerInfo {
+            puuid,
             display_name,
             summoner_level: summoner["summonerLevel"].as_u64().unwrap_or(0) as u32,
             profile_icon_id: summoner["profileIconId"].as_u64().unwrap_or(0) as u32,
@@ -300,9 +344,11 @@ pub async fn get_gameflow_phase(port: String, token: String) -> Result<GameflowS
             .map_err(|e| format!("解析JSON失败: {}", e))?;
         
         let phase = session["phase"].as_str().unwrap_or("None").to_string();
+        let queue_name = session["gameData"]["queue"]["description"].as_str().unwrap_or("").to_string();
 
         Ok(GameflowSession {
             phase,
+            queue_name,
         })
     } else {
         Err(format!("获取游戏流程状态失败: {}", response.status()))
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use super::client::lcu_get;
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EndOfGameTeam {
+    pub is_player_team: bool,
+    pub is_winning_team: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EndOfGamePlayer {
+    pub champion_id: u32,
+}
+
+/// `/lol-end-of-game/v1/eog-stats-block` 的返回结果
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EndOfGameStats {
+    pub game_id: u64,
+    pub game_length: u64,
+    pub queue_type: String,
+    pub local_player: EndOfGamePlayer,
+    pub teams: Vec<EndOfGameTeam>,
+}
+
+impl EndOfGameStats {
+    /// 本地玩家所在队伍是否获胜，无法判断时返回 None
+    pub fn is_win(&self) -> Option<bool> {
+        self.teams
+            .iter()
+            .find(|team| team.is_player_team)
+            .map(|team| team.is_winning_team)
+    }
+}
+
+/// 获取结算数据
+pub async fn get_end_of_game_stats(port: &str, token: &str) -> Result<EndOfGameStats, String> {
+    lcu_get(port, token, "/lol-end-of-game/v1/eog-stats-block").await
+}
@@ -0,0 +1,334 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use super::client::lcu_get;
+use tracing::warn;
+
+/// 每页请求的对局数量
+const PAGE_SIZE: usize = 20;
+
+#[derive(Deserialize, Default)]
+struct MatchHistoryResponse {
+    #[serde(default)]
+    games: MatchHistoryPage,
+}
+
+#[derive(Deserialize, Default)]
+struct MatchHistoryPage {
+    #[serde(default)]
+    games: Vec<MatchGame>,
+}
+
+/// 单局对局记录，当前召唤师的接口只返回自己一名参与者
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MatchGame {
+    pub game_id: u64,
+    pub game_creation: i64,
+    pub game_duration: u64,
+    pub queue_id: i32,
+    pub game_mode: String,
+    pub participants: Vec<MatchParticipant>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MatchParticipant {
+    pub champion_id: u32,
+    pub stats: ParticipantStats,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ParticipantStats {
+    pub kills: u32,
+    pub deaths: u32,
+    pub assists: u32,
+    pub total_minions_killed: u32,
+    pub neutral_minions_killed: u32,
+    pub gold_earned: u32,
+    pub total_damage_dealt_to_champions: u64,
+    pub win: bool,
+}
+
+#[derive(Deserialize)]
+struct ChampionSummary {
+    id: i64,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct QueueInfo {
+    id: i32,
+    #[serde(default)]
+    description: String,
+}
+
+/// 导出用的扁平化对局记录
+#[derive(Serialize, Clone, Debug)]
+pub struct MatchRow {
+    pub date: String,
+    pub queue: String,
+    pub champion: String,
+    pub kills: u32,
+    pub deaths: u32,
+    pub assists: u32,
+    pub kda: f64,
+    pub cs: u32,
+    pub gold: u32,
+    pub damage: u64,
+    pub win: bool,
+}
+
+/// 分页获取当前召唤师的对局记录，最多返回 `limit` 局
+pub async fn fetch_match_history(port: &str, token: &str, limit: usize) -> Result<Vec<MatchGame>, String> {
+    collect_pages(limit, |beg_index, end_index| async move {
+        let path = format!(
+            "/lol-match-history/v1/products/lol/current-summoner/matches?begIndex={}&endIndex={}",
+            beg_index, end_index
+        );
+        Ok(lcu_get::<MatchHistoryResponse>(port, token, &path).await?.games.games)
+    })
+    .await
+}
+
+/// 按页请求对局记录，`fetch_page` 的参数为 (begIndex, endIndex)
+///
+/// 接口的 `endIndex` 包含在结果内，`begIndex=0&endIndex=19` 返回 20 局。
+/// 下一页从已获取的数量开始并按 `game_id` 去重，因此即使接口按不含 `endIndex` 处理也不会重复或遗漏。
+async fn collect_pages<F, Fut>(limit: usize, mut fetch_page: F) -> Result<Vec<MatchGame>, String>
+where
+    F: FnMut(usize, usize) -> Fut,
+    Fut: Future<Output = Result<Vec<MatchGame>, String>>,
+{
+    let mut games = Vec::new();
+    let mut seen = HashSet::new();
+
+    while games.len() < limit {
+        let beg_index = games.len();
+        // 总是请求整页，最后一页多出的部分在结束后截掉
+        let end_index = beg_index + PAGE_SIZE - 1;
+        let page = fetch_page(beg_index, end_index).await?;
+        games.extend(page.into_iter().filter(|game| seen.insert(game.game_id)));
+
+        // 没有新记录说明已经到头
+        if games.len() == beg_index {
+            break;
+        }
+    }
+
+    games.truncate(limit);
+    Ok(games)
+}
+
+/// 获取英雄ID到名称的映射，失败时返回空表
+pub async fn fetch_champion_names(port: &str, token: &str) -> HashMap<u32, String> {
+    match lcu_get::<Vec<ChampionSummary>>(port, token, "/lol-game-data/assets/v1/champion-summary.json").await {
+        Ok(champions) => champions
+            .into_iter()
+            .filter(|c| c.id > 0)
+            .map(|c| (c.id as u32, c.name))
+            .collect(),
+        Err(e) => {
+            warn!("获取英雄列表失败: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// 获取队列ID到名称的映射，失败时返回空表
+async fn fetch_queue_names(port: &str, token: &str) -> HashMap<i32, String> {
+    match lcu_get::<Vec<QueueInfo>>(port, token, "/lol-game-queues/v1/queues").await {
+        Ok(queues) => queues
+            .into_iter()
+            .filter(|q| !q.description.is_empty())
+            .map(|q| (q.id, q.description))
+            .collect(),
+        Err(e) => {
+            warn!("获取队列列表失败: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// 将对局记录展开为导出行，名称缺失时使用ID
+pub fn flatten_matches(
+    games: &[MatchGame],
+    champion_names: &HashMap<u32, String>,
+    queue_names: &HashMap<i32, String>,
+) -> Vec<MatchRow> {
+    games
+        .iter()
+        .filter_map(|game| {
+            let participant = game.participants.first()?;
+            let stats = &participant.stats;
+
+            let date = chrono::DateTime::from_timestamp_millis(game.game_creation)
+                .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            let kda = (stats.kills + stats.assists) as f64 / stats.deaths.max(1) as f64;
+
+            Some(MatchRow {
+                date,
+                queue: queue_names
+                    .get(&game.queue_id)
+                    .cloned()
+                    .unwrap_or_else(|| game.queue_id.to_string()),
+                champion: champion_names
+                    .get(&participant.champion_id)
+                    .cloned()
+                    .unwrap_or_else(|| participant.champion_id.to_string()),
+                kills: stats.kills,
+                deaths: stats.deaths,
+                assists: stats.assists,
+                kda: (kda * 100.0).round() / 100.0,
+                cs: stats.total_minions_killed + stats.neutral_minions_killed,
+                gold: stats.gold_earned,
+                damage: stats.total_damage_dealt_to_champions,
+                win: stats.win,
+            })
+        })
+        .collect()
+}
+
+/// 获取对局记录并展开为导出行
+pub async fn fetch_match_rows(port: &str, token: &str, limit: usize) -> Result<Vec<MatchRow>, String> {
+    let (games, champion_names, queue_names) = tokio::join!(
+        fetch_match_history(port, token, limit),
+        fetch_champion_names(port, token),
+        fetch_queue_names(port, token),
+    );
+    Ok(flatten_matches(&games?, &champion_names, &queue_names))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// `/lol-match-history/v1/products/lol/current-summoner/matches` 的返回结果，省略了无关字段
+    const MATCH_HISTORY_FIXTURE: &str = r#"{
+        "accountId": 1,
+        "games": {
+            "gameBeginIndex": 0,
+            "gameEndIndex": 2,
+            "gameCount": 3,
+            "games": [
+                {
+                    "gameId": 7001,
+                    "gameCreation": 1700000000000,
+                    "gameDuration": 1835,
+                    "gameMode": "CLASSIC",
+                    "queueId": 420,
+                    "participants": [{
+                        "championId": 103,
+                        "stats": {
+                            "kills": 7, "deaths": 3, "assists": 9,
+                            "totalMinionsKilled": 180, "neutralMinionsKilled": 12,
+                            "goldEarned": 12450, "totalDamageDealtToChampions": 25310, "win": true
+                        }
+                    }]
+                },
+                {
+                    "gameId": 7000,
+                    "gameCreation": 1699990000000,
+                    "gameDuration": 1200,
+                    "gameMode": "ARAM",
+                    "queueId": 450,
+                    "participants": [{
+                        "championId": 9999,
+                        "stats": { "kills": 2, "deaths": 0, "assists": 5, "win": false }
+                    }]
+                },
+                { "gameId": 6999, "queueId": 420, "participants": [] }
+            ]
+        }
+    }"#;
+
+    fn game(game_id: u64) -> MatchGame {
+        MatchGame {
+            game_id,
+            ..Default::default()
+        }
+    }
+
+    /// 按 (begIndex, endIndex) 返回 `total` 局记录的替身接口，记录每次请求
+    fn fake_pages(
+        total: u64,
+        inclusive: bool,
+        requests: &Mutex<Vec<(usize, usize)>>,
+    ) -> impl FnMut(usize, usize) -> std::future::Ready<Result<Vec<MatchGame>, String>> + '_ {
+        move |beg_index, end_index| {
+            requests.lock().unwrap().push((beg_index, end_index));
+            let end = if inclusive { end_index as u64 + 1 } else { end_index as u64 };
+            std::future::ready(Ok((beg_index as u64..end.min(total)).map(game).collect()))
+        }
+    }
+
+    fn ids(games: &[MatchGame]) -> Vec<u64> {
+        games.iter().map(|game| game.game_id).collect()
+    }
+
+    #[test]
+    fn fixture_flattens_to_rows() {
+        let response: MatchHistoryResponse = serde_json::from_str(MATCH_HISTORY_FIXTURE).unwrap();
+        let games = response.games.games;
+        assert_eq!(games.len(), 3);
+
+        let champions = HashMap::from([(103, "阿狸".to_string())]);
+        let queues = HashMap::from([(420, "单排/双排".to_string())]);
+        let rows = flatten_matches(&games, &champions, &queues);
+
+        // 没有参与者的对局被跳过
+        assert_eq!(rows.len(), 2);
+        let ranked = &rows[0];
+        let expected_date = chrono::DateTime::from_timestamp_millis(1700000000000)
+            .unwrap()
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string();
+        assert_eq!(ranked.date, expected_date);
+        assert_eq!((ranked.queue.as_str(), ranked.champion.as_str()), ("单排/双排", "阿狸"));
+        assert_eq!((ranked.kills, ranked.deaths, ranked.assists), (7, 3, 9));
+        assert_eq!(ranked.kda, 5.33);
+        assert_eq!((ranked.cs, ranked.gold, ranked.damage), (192, 12450, 25310));
+        assert!(ranked.win);
+
+        // 名称缺失时使用ID，零死亡按一次计算
+        let aram = &rows[1];
+        assert_eq!((aram.queue.as_str(), aram.champion.as_str()), ("450", "9999"));
+        assert_eq!(aram.kda, 7.0);
+        assert!(!aram.win);
+    }
+
+    #[tokio::test]
+    async fn pages_are_contiguous_with_inclusive_end_index() {
+        let requests = Mutex::new(Vec::new());
+        let games = collect_pages(45, fake_pages(100, true, &requests)).await.unwrap();
+        assert_eq!(ids(&games), (0..45).collect::<Vec<_>>());
+        assert_eq!(*requests.lock().unwrap(), vec![(0, 19), (20, 39), (40, 59)]);
+
+        let requests = Mutex::new(Vec::new());
+        let games = collect_pages(100, fake_pages(25, true, &requests)).await.unwrap();
+        assert_eq!(ids(&games), (0..25).collect::<Vec<_>>());
+        assert_eq!(*requests.lock().unwrap(), vec![(0, 19), (20, 39), (25, 44)]);
+    }
+
+    #[tokio::test]
+    async fn pages_do_not_skip_with_exclusive_end_index() {
+        let requests = Mutex::new(Vec::new());
+        let games = collect_pages(45, fake_pages(100, false, &requests)).await.unwrap();
+        assert_eq!(ids(&games), (0..45).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn overlapping_pages_are_deduplicated() {
+        // 每页多返回上一页的最后一局
+        let games = collect_pages(30, |beg_index, end_index| {
+            std::future::ready(Ok((beg_index.saturating_sub(1) as u64..=end_index as u64).map(game).collect()))
+        })
+        .await
+        .unwrap();
+        assert_eq!(ids(&games), (0..30).collect::<Vec<_>>());
+    }
+}
@@ -1,4 +1,16 @@
 /// 英雄联盟客户端相关模块
 pub mod client;
+pub mod ranked;
+pub mod profile;
+pub mod match_history;
+pub mod champ_select;
+pub mod end_of_game;
+pub mod api;
 
-pub use client::*;
\ No newline at end of file
+pub use client::*;
+pub use ranked::*;
+pub use profile::*;
+pub use match_history::*;
+pub use champ_select::*;
+pub use end_of_game::*;
+pub use api::{LcuApi, LiveLcu};
\ No newline at end of file
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use super::client::lcu_get;
+use super::ranked::{RankedQueueStats, RankedStats};
+use tracing::warn;
+
+/// 资料中展示的英雄成就数量
+const TOP_MASTERY_COUNT: usize = 3;
+
+/// 单个英雄的成就数据
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ChampionMastery {
+    #[serde(alias = "championId", default)]
+    pub champion_id: u32,
+    #[serde(alias = "championLevel", default)]
+    pub champion_level: u32,
+    #[serde(alias = "championPoints", default)]
+    pub champion_points: u64,
+}
+
+#[derive(Deserialize, Default)]
+struct HonorProfile {
+    #[serde(rename = "honorLevel", default)]
+    honor_level: u32,
+}
+
+#[derive(Deserialize, Default)]
+struct ChallengeTitle {
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Deserialize, Default)]
+struct ChallengeSummary {
+    #[serde(default)]
+    title: Option<ChallengeTitle>,
+}
+
+/// 玩家资料，补充 `SummonerInfo` 之外的段位、成就、荣誉等信息
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct PlayerProfile {
+    pub solo_rank: Option<RankedQueueStats>,
+    pub flex_rank: Option<RankedQueueStats>,
+    pub top_masteries: Vec<ChampionMastery>,
+    pub honor_level: Option<u32>,
+    pub challenge_title: Option<String>,
+}
+
+/// 并发获取玩家资料，单项失败时对应字段留空
+#[tauri::command]
+pub async fn get_player_profile(port: String, token: String) -> Result<PlayerProfile, String> {
+    let (ranked, masteries, honor, challenges) = tokio::join!(
+        lcu_get::<RankedStats>(&port, &token, "/lol-ranked/v1/current-ranked-stats"),
+        lcu_get::<Vec<ChampionMastery>>(&port, &token, "/lol-champion-mastery/v1/local-player/champion-mastery"),
+        lcu_get::<HonorProfile>(&port, &token, "/lol-honor-v2/v1/profile"),
+        lcu_get::<ChallengeSummary>(&port, &token, "/lol-challenges/v1/summary-player-data/local-player"),
+    );
+
+    if ranked.is_err() && masteries.is_err() && honor.is_err() && challenges.is_err() {
+        return Err("获取玩家资料失败".to_string());
+    }
+
+    let mut profile = PlayerProfile::default();
+
+    match ranked {
+        Ok(stats) => {
+            profile.solo_rank = stats.queue("RANKED_SOLO_5x5").cloned();
+            profile.flex_rank = stats.queue("RANKED_FLEX_SR").cloned();
+        }
+        Err(e) => warn!("获取段位信息失败: {}", e),
+    }
+
+    match masteries {
+        Ok(mut masteries) => {
+            masteries.sort_by_key(|m| std::cmp::Reverse(m.champion_points));
+            masteries.truncate(TOP_MASTERY_COUNT);
+            profile.top_masteries = masteries;
+        }
+        Err(e) => warn!("获取英雄成就失败: {}", e),
+    }
+
+    match honor {
+        Ok(honor) => profile.honor_level = Some(honor.honor_level),
+        Err(e) => warn!("获取荣誉等级失败: {}", e),
+    }
+
+    match challenges {
+        Ok(summary) => {
+            profile.challenge_title = summary
+                .title
+                .map(|title| title.name)
+                .filter(|name| !name.is_empty());
+        }
+        Err(e) => warn!("获取挑战称号失败: {}", e),
+    }
+
+    Ok(profile)
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use super::client::lcu_get;
+
+/// 段位顺序，从低到高
+const TIERS: [&str; 10] = [
+    "IRON", "BRONZE", "SILVER", "GOLD", "PLATINUM",
+    "EMERALD", "DIAMOND", "MASTER", "GRANDMASTER", "CHALLENGER",
+];
+
+/// 大师及以上段位在 `TIERS` 中的起始下标，这些段位只有胜点没有小段
+const APEX_TIER_INDEX: usize = 7;
+
+/// 单个排位队列的数据
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RankedQueueStats {
+    #[serde(alias = "queueType", default)]
+    pub queue_type: String,
+    #[serde(default)]
+    pub tier: String,
+    #[serde(default)]
+    pub division: String,
+    #[serde(alias = "leaguePoints", default)]
+    pub league_points: i32,
+    #[serde(default)]
+    pub wins: u32,
+    #[serde(default)]
+    pub losses: u32,
+}
+
+/// `/lol-ranked/v1/current-ranked-stats` 的返回结果
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RankedStats {
+    #[serde(default)]
+    pub queues: Vec<RankedQueueStats>,
+}
+
+impl RankedQueueStats {
+    /// 段位与小段组成的排序键，未定级时返回 None
+    pub fn rank_key(&self) -> Option<(usize, usize)> {
+        let tier = TIERS.iter().position(|t| *t == self.tier)?;
+        if tier >= APEX_TIER_INDEX {
+            return Some((tier, 0));
+        }
+        let division = match self.division.as_str() {
+            "IV" => 0,
+            "III" => 1,
+            "II" => 2,
+            "I" => 3,
+            _ => return None,
+        };
+        Some((tier, division))
+    }
+
+    /// 将段位折算为连续分数，用于计算跨小段的胜点变化
+    pub fn rank_score(&self) -> Option<i32> {
+        let (tier, division) = self.rank_key()?;
+        // 大师、宗师、王者共用同一套胜点
+        let base = tier.min(APEX_TIER_INDEX) * 400 + division * 100;
+        Some(base as i32 + self.league_points)
+    }
+}
+
+impl RankedStats {
+    /// 按队列类型查找，例如 `RANKED_SOLO_5x5`、`RANKED_FLEX_SR`
+    pub fn queue(&self, queue_type: &str) -> Option<&RankedQueueStats> {
+        self.queues.iter().find(|q| q.queue_type == queue_type)
+    }
+}
+
+#[tauri::command]
+pub async fn get_ranked_stats(port: String, token: String) -> Result<RankedStats, String> {
+    lcu_get(&port, &token, "/lol-ranked/v1/current-ranked-stats").await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(tier: &str, division: &str, league_points: i32) -> RankedQueueStats {
+        RankedQueueStats {
+            tier: tier.to_string(),
+            division: division.to_string(),
+            league_points,
+            ..Default::default()
+        }
+    }
+
+    fn score_delta(from: RankedQueueStats, to: RankedQueueStats) -> i32 {
+        to.rank_score().unwrap() - from.rank_score().unwrap()
+    }
+
+    #[test]
+    fn rank_key_orders_tiers_and_divisions() {
+        assert_eq!(queue("IRON", "IV", 0).rank_key(), Some((0, 0)));
+        assert_eq!(queue("GOLD", "II", 0).rank_key(), Some((3, 2)));
+        assert_eq!(queue("MASTER", "I", 0).rank_key(), Some((7, 0)));
+        assert_eq!(queue("CHALLENGER", "", 0).rank_key(), Some((9, 0)));
+        assert_eq!(queue("", "", 0).rank_key(), None);
+        assert_eq!(queue("GOLD", "NA", 0).rank_key(), None);
+
+        assert!(queue("GOLD", "I", 0).rank_key() < queue("PLATINUM", "IV", 0).rank_key());
+        assert!(queue("DIAMOND", "I", 0).rank_key() < queue("MASTER", "I", 0).rank_key());
+    }
+
+    #[test]
+    fn rank_score_counts_league_points_across_divisions_and_tiers() {
+        assert_eq!(queue("GOLD", "I", 75).rank_score(), Some(1575));
+        assert_eq!(queue("", "", 75).rank_score(), None);
+
+        // 同一小段内和跨小段
+        assert_eq!(score_delta(queue("SILVER", "II", 40), queue("SILVER", "II", 61)), 21);
+        assert_eq!(score_delta(queue("SILVER", "II", 90), queue("SILVER", "I", 8)), 18);
+        // 跨段位晋级和降级
+        assert_eq!(score_delta(queue("GOLD", "I", 90), queue("PLATINUM", "IV", 15)), 25);
+        assert_eq!(score_delta(queue("PLATINUM", "IV", 0), queue("GOLD", "I", 80)), -20);
+        assert_eq!(score_delta(queue("DIAMOND", "I", 90), queue("MASTER", "I", 0)), 10);
+        // 大师及以上共用胜点
+        assert_eq!(score_delta(queue("MASTER", "I", 350), queue("GRANDMASTER", "I", 372)), 22);
+    }
+}
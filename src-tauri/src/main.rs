@@ -2,6 +2,12 @@
 #![cfg_attr(target_os = "windows", windows_subsystem = "windows")]
 
 fn main() {
+    // 命令行子命令和启动参数在提权和启动界面之前处理
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = micept_lib::cli::run(&args) {
+        std::process::exit(code);
+    }
+    
     #[cfg(target_os = "windows")]
     {
         // 检查是否有管理员权限
@@ -12,8 +18,8 @@ fn main() {
             // 获取当前可执行文件路径
             if let Ok(current_exe) = std::env::current_exe() {
                 if let Some(exe_path) = current_exe.to_str() {
-                    // 使用runas库以管理员权限重启应用
-                    match runas::Command::new(exe_path).gui(true).status() {
+                    // 使用runas库以管理员权限重启应用，保留启动参数
+                    match runas::Command::new(exe_path).args(&args).gui(true).status() {
                         Ok(_) => {
                             // 重启成功，退出当前实例
                             std::process::exit(0);
@@ -0,0 +1,183 @@
+use crate::core::{events::EventSink, frontend::Frontend};
+use crate::lol::api::{LcuApi, LcuFuture};
+use crate::lol::{
+    ChampSelectSession, EndOfGameStats, GameflowSession, LcuAuthInfo, PlayerProfile, RankedStats, SummonerInfo,
+};
+use crate::ui::notification::{NotificationSink, Notifier, SoundSink};
+use crate::ui::window::WindowController;
+use futures_util::future::BoxFuture;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 按 `名称:内容` 记录事件、通知和提示音
+#[derive(Clone, Default)]
+pub struct Recorder(pub Arc<Mutex<Vec<String>>>);
+
+impl Recorder {
+    /// 指定名称的记录内容，按发生顺序排列
+    pub fn named(&self, name: &str) -> Vec<String> {
+        let prefix = format!("{}:", name);
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|entry| entry.strip_prefix(&prefix).map(str::to_string))
+            .collect()
+    }
+}
+
+impl EventSink for Recorder {
+    fn send(&self, name: &'static str, payload: &serde_json::Value) {
+        self.0.lock().unwrap().push(format!("{}:{}", name, payload));
+    }
+}
+
+impl NotificationSink for Recorder {
+    fn show(&self, title: &str, _body: &str) {
+        self.0.lock().unwrap().push(format!("notify:{}", title));
+    }
+}
+
+impl SoundSink for Recorder {
+    fn play(&self, path: &str) {
+        self.0.lock().unwrap().push(format!("sound:{}", path));
+    }
+}
+
+/// 记录显示和隐藏操作的窗口
+#[derive(Default)]
+pub struct FakeWindow {
+    pub visible: AtomicBool,
+    pub calls: Mutex<Vec<bool>>,
+}
+
+impl WindowController for FakeWindow {
+    fn is_visible(&self) -> bool {
+        self.visible.load(Ordering::SeqCst)
+    }
+
+    fn set_visible(&self, visible: bool) -> Result<(), String> {
+        self.visible.store(visible, Ordering::SeqCst);
+        self.calls.lock().unwrap().push(visible);
+        Ok(())
+    }
+
+    fn focus(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn set_mouse_through(&self, _enabled: bool) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn set_position(&self, _x: i32, _y: i32) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// 按脚本依次返回游戏阶段的客户端，脚本用完后保持最后一个阶段
+pub struct FakeLcu {
+    phases: Mutex<VecDeque<&'static str>>,
+    last_phase: Mutex<&'static str>,
+    pub finished: AtomicBool,
+    pub accepts: AtomicU32,
+}
+
+impl FakeLcu {
+    pub fn new(phases: &[&'static str]) -> Self {
+        Self {
+            phases: Mutex::new(phases.iter().copied().collect()),
+            last_phase: Mutex::new("None"),
+            finished: AtomicBool::new(false),
+            accepts: AtomicU32::new(0),
+        }
+    }
+}
+
+impl LcuApi for FakeLcu {
+    fn discover(&self) -> LcuFuture<'_, LcuAuthInfo> {
+        Box::pin(async {
+            Ok(LcuAuthInfo {
+                port: "2999".to_string(),
+                token: "token".to_string(),
+                is_connected: true,
+            })
+        })
+    }
+
+    fn validate(&self, _auth: &LcuAuthInfo) -> LcuFuture<'_, ()> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn summoner_info(&self, _auth: &LcuAuthInfo) -> LcuFuture<'_, SummonerInfo> {
+        Box::pin(async {
+            Ok(SummonerInfo {
+                puuid: "fake-puuid".to_string(),
+                display_name: "Tester".to_string(),
+                summoner_level: 30,
+                profile_icon_id: 1,
+                xp_since_last_level: 0,
+                xp_until_next_level: 100,
+            })
+        })
+    }
+
+    fn player_profile(&self, _auth: &LcuAuthInfo) -> LcuFuture<'_, PlayerProfile> {
+        Box::pin(async { Ok(PlayerProfile::default()) })
+    }
+
+    fn ranked_stats(&self, _auth: &LcuAuthInfo) -> LcuFuture<'_, RankedStats> {
+        Box::pin(async { Ok(RankedStats::default()) })
+    }
+
+    fn gameflow_session(&self, _auth: &LcuAuthInfo) -> LcuFuture<'_, GameflowSession> {
+        let mut last = self.last_phase.lock().unwrap();
+        match self.phases.lock().unwrap().pop_front() {
+            Some(phase) => *last = phase,
+            None => self.finished.store(true, Ordering::SeqCst),
+        }
+        let phase = last.to_string();
+        Box::pin(async move {
+            Ok(GameflowSession {
+                phase,
+                queue_name: "排位赛".to_string(),
+            })
+        })
+    }
+
+    fn champ_select_session(&self, _auth: &LcuAuthInfo) -> LcuFuture<'_, ChampSelectSession> {
+        Box::pin(async { Ok(ChampSelectSession::default()) })
+    }
+
+    fn end_of_game_stats(&self, _auth: &LcuAuthInfo) -> LcuFuture<'_, EndOfGameStats> {
+        Box::pin(async { Ok(EndOfGameStats::default()) })
+    }
+
+    fn champion_names(&self, _auth: &LcuAuthInfo) -> BoxFuture<'_, HashMap<u32, String>> {
+        Box::pin(async { HashMap::new() })
+    }
+
+    fn accept_match(&self, _auth: &LcuAuthInfo) -> LcuFuture<'_, ()> {
+        self.accepts.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// 用替身组装的界面接口，事件和通知分别记录
+pub fn frontend(events: &Recorder, notifications: &Recorder, window: Arc<FakeWindow>) -> Frontend {
+    Frontend {
+        events: Arc::new(events.clone()),
+        window,
+        notifier: Arc::new(Notifier::new(Box::new(notifications.clone()), Box::new(notifications.clone()))),
+    }
+}
+
+/// 每个测试独立的空临时目录
+pub fn temp_dir(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("micept-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    directory
+}
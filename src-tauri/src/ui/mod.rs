@@ -1,3 +1,4 @@
 /// UI模块 - 用户界面相关功能
 pub mod tray;
-pub mod window;
\ No newline at end of file
+pub mod window;
+pub mod notification;
\ No newline at end of file
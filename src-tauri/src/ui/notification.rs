@@ -0,0 +1,203 @@
+use crate::utils::config::NotificationConfig;
+use chrono::NaiveTime;
+use std::process::Command;
+use tauri_plugin_notification::NotificationExt;
+use tracing::{debug, warn};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// 需要提醒用户的游戏事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationEvent {
+    ReadyCheck,
+    ChampSelect,
+    PickTurn,
+    GameStart,
+}
+
+impl NotificationEvent {
+    /// 该事件在配置中是否开启
+    fn enabled(&self, config: &NotificationConfig) -> bool {
+        match self {
+            NotificationEvent::ReadyCheck => config.ready_check,
+            NotificationEvent::ChampSelect => config.champ_select,
+            NotificationEvent::PickTurn => config.pick_turn,
+            NotificationEvent::GameStart => config.game_start,
+        }
+    }
+
+    /// 通知标题和正文
+    fn message(&self) -> (&'static str, &'static str) {
+        match self {
+            NotificationEvent::ReadyCheck => ("找到对局", "请及时接受对局"),
+            NotificationEvent::ChampSelect => ("英雄选择", "已进入英雄选择阶段"),
+            NotificationEvent::PickTurn => ("轮到你了", "请选择你的英雄"),
+            NotificationEvent::GameStart => ("游戏开始", "对局已开始加载"),
+        }
+    }
+}
+
+/// 桌面通知输出
+pub trait NotificationSink: Send + Sync {
+    fn show(&self, title: &str, body: &str);
+}
+
+/// 提示音输出
+pub trait SoundSink: Send + Sync {
+    fn play(&self, path: &str);
+}
+
+/// 通知分发器，根据配置决定是否发出通知和提示音
+pub struct Notifier {
+    notification: Box<dyn NotificationSink>,
+    sound: Box<dyn SoundSink>,
+}
+
+impl Notifier {
+    pub fn new(notification: Box<dyn NotificationSink>, sound: Box<dyn SoundSink>) -> Self {
+        Self { notification, sound }
+    }
+
+    /// 使用系统通知和系统播放器创建分发器
+    pub fn system(app_handle: tauri::AppHandle) -> Self {
+        Self::with_system_sound(Box::new(TauriNotificationSink { app_handle }))
+    }
+
+    /// 使用指定的通知输出和系统播放器创建分发器
+    pub fn with_system_sound(notification: Box<dyn NotificationSink>) -> Self {
+        Self::new(notification, Box::new(SystemSoundSink))
+    }
+
+    /// 分发一个事件，返回是否实际发出了通知
+    pub fn notify(&self, event: NotificationEvent, config: &NotificationConfig, now: NaiveTime) -> bool {
+        if !event.enabled(config) {
+            return false;
+        }
+        if config.quiet_hours.contains(now) {
+            debug!("免打扰时段内，跳过通知: {:?}", event);
+            return false;
+        }
+
+        let (title, body) = event.message();
+        self.notification.show(title, body);
+
+        if config.sound_enabled {
+            if let Some(path) = config.sound_file.as_deref().filter(|p| !p.is_empty()) {
+                self.sound.play(path);
+            }
+        }
+        true
+    }
+}
+
+/// 通过 Tauri 通知插件发送系统通知
+struct TauriNotificationSink {
+    app_handle: tauri::AppHandle,
+}
+
+impl NotificationSink for TauriNotificationSink {
+    fn show(&self, title: &str, body: &str) {
+        if let Err(e) = self
+            .app_handle
+            .notification()
+            .builder()
+            .title(title)
+            .body(body)
+            .show()
+        {
+            warn!("发送系统通知失败: {}", e);
+        }
+    }
+}
+
+/// 调用系统自带的播放器播放提示音，不阻塞调用方
+struct SystemSoundSink;
+
+impl SoundSink for SystemSoundSink {
+    fn play(&self, path: &str) {
+        #[cfg(target_os = "windows")]
+        let child = {
+            let script = format!(
+                "(New-Object Media.SoundPlayer '{}').PlaySync()",
+                path.replace('\'', "''")
+            );
+            Command::new("powershell")
+                .args(["-NoProfile", "-Command", &script])
+                .creation_flags(0x08000000) // CREATE_NO_WINDOW
+                .spawn()
+        };
+
+        #[cfg(target_os = "macos")]
+        let child = Command::new("afplay").arg(path).spawn();
+
+        #[cfg(all(unix, not(target_os = "macos")))]
+        let child = Command::new("paplay")
+            .arg(path)
+            .spawn()
+            .or_else(|_| Command::new("aplay").arg(path).spawn());
+
+        match child {
+            Ok(mut child) => {
+                // 在单独线程中回收子进程
+                std::thread::spawn(move || {
+                    let _ = child.wait();
+                });
+            }
+            Err(e) => warn!("播放提示音失败: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Recorder;
+
+    fn notifier() -> (Notifier, Recorder) {
+        let recorder = Recorder::default();
+        let notifier = Notifier::new(Box::new(recorder.clone()), Box::new(recorder.clone()));
+        (notifier, recorder)
+    }
+
+    fn time(text: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(text, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn disabled_event_is_not_emitted() {
+        let (notifier, recorder) = notifier();
+        let config = NotificationConfig::default();
+
+        assert!(!notifier.notify(NotificationEvent::GameStart, &config, time("12:00")));
+        assert!(recorder.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn enabled_event_emits_notification_and_sound() {
+        let (notifier, recorder) = notifier();
+        let config = NotificationConfig {
+            sound_enabled: true,
+            sound_file: Some("ready.wav".to_string()),
+            ..Default::default()
+        };
+
+        assert!(notifier.notify(NotificationEvent::ReadyCheck, &config, time("12:00")));
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec!["notify:找到对局".to_string(), "sound:ready.wav".to_string()]
+        );
+    }
+
+    #[test]
+    fn quiet_hours_across_midnight_suppress_notifications() {
+        let (notifier, recorder) = notifier();
+        let mut config = NotificationConfig::default();
+        config.quiet_hours.enabled = true;
+
+        assert!(!notifier.notify(NotificationEvent::ReadyCheck, &config, time("23:30")));
+        assert!(!notifier.notify(NotificationEvent::ReadyCheck, &config, time("07:59")));
+        assert!(notifier.notify(NotificationEvent::ReadyCheck, &config, time("08:00")));
+        assert_eq!(recorder.0.lock().unwrap().len(), 1);
+    }
+}
@@ -1,122 +1,101 @@
-use crate::core::AppState;
+use crate::core::{
+    diagnostics,
+    events,
+    profiles,
+    session::{publish_summary, SessionAction},
+    shutdown,
+    state::StateAction,
+    AppState,
+};
+use crate::ui::window::WindowController;
+use crate::utils::{autostart, config::ConfigUpdate, logging, AppConfig};
 use tauri::{
-    menu::{Menu, MenuItem, CheckMenuItem},
+    menu::{Menu, MenuItem, CheckMenuItem, PredefinedMenuItem, Submenu, IsMenuItem},
     tray::TrayIconBuilder,
-    Manager,
+    Manager, Wry,
 };
+use tauri_plugin_opener::OpenerExt;
+use tracing::{debug, info, warn};
+
+/// 配置档案菜单项ID的前缀，后接 puuid 或 `default`
+const PROFILE_MENU_PREFIX: &str = "profile:";
 
 /// 创建系统托盘
-pub fn create_tray(
-    app: &tauri::App,
-    app_state: &AppState,
-    mouse_through_state: bool,
-    auto_accept_state: bool,
-    auto_hide_state: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
-    let mouse_through_item = CheckMenuItem::with_id(
-        app,
-        "mouse_through",
-        "鼠标穿透",
-        true,
-        mouse_through_state,
-        None::<&str>,
-    )?;
-    let auto_accept_item = CheckMenuItem::with_id(
-        app,
-        "auto_accept",
-        "自动接受",
-        true,
-        auto_accept_state,
-        None::<&str>,
-    )?;
-    let auto_hide_item = CheckMenuItem::with_id(
-        app,
-        "auto_hide",
-        "自动隐藏",
-        true,
-        auto_hide_state,
-        None::<&str>,
-    )?;
-    let menu = Menu::with_items(app, &[&mouse_through_item, &auto_accept_item, &auto_hide_item, &quit_item])?;
+pub fn create_tray(app: &tauri::App, app_state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
+    let menu = app_state.read(|snapshot| build_tray_menu(app, &snapshot.config))?;
+    let tooltip = app_state.read(|snapshot| snapshot.session.summary().tooltip());
 
-    let window = app.get_webview_window("main").unwrap();
-    let window_clone = window.clone();
-    let window_for_tray = window.clone();
+    let window_for_tray = app.get_webview_window("main").unwrap();
     let state_for_tray = app_state.clone();
     let state_for_menu = app_state.clone();
 
     let _tray = TrayIconBuilder::with_id("main")
         .icon(app.default_window_icon().unwrap().clone())
         .menu(&menu)
+        .tooltip(tooltip)
         .show_menu_on_left_click(false)
-        .on_tray_icon_event(move |tray, event| {
-            match event {
-                tauri::tray::TrayIconEvent::Click {
-                    button: tauri::tray::MouseButton::Left,
-                    button_state: tauri::tray::MouseButtonState::Up,
-                    ..
-                } => {
-                    // 左键点击切换窗口显示/隐藏
-                    if let Ok(is_visible) = window_for_tray.is_visible() {
-                        if is_visible {
-                            let _ = window_for_tray.hide();
-                            // 保存窗口可见性状态
-                            state_for_tray
-                                .config
-                                .lock()
-                                .unwrap()
-                                .update_window_visible(false);
-                        } else {
-                            let _ = window_for_tray.show();
-                            // 保存窗口可见性状态
-                            state_for_tray
-                                .config
-                                .lock()
-                                .unwrap()
-                                .update_window_visible(true);
-                        }
-                        
-                        // 手动切换窗口时，自动关闭auto_hide功能
-                        let current_auto_hide = *state_for_tray.auto_hide.lock().unwrap();
-                        if current_auto_hide {
-                            println!("Manual window toggle detected, disabling auto_hide");
-                            *state_for_tray.auto_hide.lock().unwrap() = false;
-                            // 更新配置文件
-                            state_for_tray.config.lock().unwrap().update_auto_hide(false);
-                            
-                            // 更新托盘菜单以反映auto_hide状态变化
-                            let app_handle = tray.app_handle();
-                            update_tray_menu(
-                                &app_handle,
-                                &state_for_tray,
-                                *state_for_tray.mouse_through.lock().unwrap(),
-                                *state_for_tray.auto_accept.lock().unwrap(),
-                                false, // auto_hide现在为false
-                            );
-                        }
+        .on_tray_icon_event(move |_tray, event| {
+            if let tauri::tray::TrayIconEvent::Click {
+                button: tauri::tray::MouseButton::Left,
+                button_state: tauri::tray::MouseButtonState::Up,
+                ..
+            } = event
+            {
+                // 左键点击切换窗口显示/隐藏
+                if let Ok(is_visible) = window_for_tray.is_visible() {
+                    if is_visible {
+                        let _ = window_for_tray.hide();
+                        // 保存窗口可见性状态
+                        state_for_tray.update_config(ConfigUpdate::WindowVisible(false));
+                    } else {
+                        let _ = window_for_tray.show();
+                        // 保存窗口可见性状态
+                        state_for_tray.update_config(ConfigUpdate::WindowVisible(true));
+                    }
+                    
+                    // 手动切换窗口时，自动关闭auto_hide功能
+                    if state_for_tray.read(|snapshot| snapshot.config.auto_hide) {
+                        info!("Manual window toggle detected, disabling auto_hide");
+                        set_auto_hide(&state_for_tray, false);
                     }
                 }
-                _ => {}
             }
         })
         .on_menu_event(move |app, event| match event.id.as_ref() {
             "quit" => {
-                println!("quit menu item was clicked");
-                *state_for_menu.is_running.lock().unwrap() = false;
-                std::process::exit(0);
+                debug!("quit menu item was clicked");
+                // 等待后台任务和配置写入完成后再退出
+                shutdown::quit(app.clone(), state_for_menu.clone());
             }
             "mouse_through" => {
-                handle_mouse_through_event(app, &state_for_menu, &window_clone);
+                handle_mouse_through_event(app, &state_for_menu);
             }
             "auto_accept" => {
-                handle_auto_accept_event(app, &state_for_menu);
+                handle_auto_accept_event(&state_for_menu);
             }
             "auto_hide" => {
-                handle_auto_hide_event(app, &state_for_menu);
+                handle_auto_hide_event(&state_for_menu);
+            }
+            "discord_presence" => {
+                handle_discord_presence_event(app, &state_for_menu);
+            }
+            "autostart" => {
+                handle_autostart_event(app, &state_for_menu);
+            }
+            "open_log_folder" => {
+                handle_open_log_folder_event(app);
+            }
+            "export_diagnostics" => {
+                handle_export_diagnostics_event(app, &state_for_menu);
+            }
+            "reset_session" => {
+                handle_reset_session_event(app, &state_for_menu);
+            }
+            id if id.starts_with(PROFILE_MENU_PREFIX) => {
+                handle_profile_event(app, &state_for_menu, &id[PROFILE_MENU_PREFIX.len()..]);
             }
             _ => {
-                println!("menu item {:?} not handled", event.id);
+                debug!("menu item {:?} not handled", event.id);
             }
         })
         .build(app)?;
@@ -125,119 +104,285 @@ pub fn create_tray(
 }
 
 /// 处理鼠标穿透菜单事件
-fn handle_mouse_through_event(
-    app: &tauri::AppHandle,
-    state: &AppState,
-    window: &tauri::WebviewWindow,
-) {
-    println!("mouse through menu item was clicked");
+fn handle_mouse_through_event(app: &tauri::AppHandle, state: &AppState) {
+    debug!("mouse through menu item was clicked");
+
+    // 获取当前状态并切换
+    let new_state = !state.read(|snapshot| snapshot.config.mouse_through);
+    set_mouse_through(app, state, new_state);
+}
+
+/// 处理自动接受菜单事件
+fn handle_auto_accept_event(state: &AppState) {
+    debug!("auto accept menu item was clicked");
 
     // 获取当前状态并切换
-    let mut current_state = state.mouse_through.lock().unwrap();
-    let new_state = !*current_state;
-    *current_state = new_state;
+    let new_state = !state.read(|snapshot| snapshot.config.auto_accept());
+    set_auto_accept(state, new_state);
+}
 
-    // 更新配置文件
-    state.config.lock().unwrap().update_mouse_through(new_state);
+/// 处理自动隐藏菜单事件
+fn handle_auto_hide_event(state: &AppState) {
+    debug!("auto hide menu item was clicked");
+
+    // 获取当前状态并切换
+    let new_state = !state.read(|snapshot| snapshot.config.auto_hide);
+    set_auto_hide(state, new_state);
+}
+
+/// 处理Discord状态菜单事件
+fn handle_discord_presence_event(app: &tauri::AppHandle, state: &AppState) {
+    debug!("discord presence menu item was clicked");
+
+    // 获取当前状态并切换
+    let new_state = !state.read(|snapshot| snapshot.config.discord.enabled);
+    set_discord_presence(app, state, new_state);
+}
 
-    println!("Current state: {}, New state: {}", !new_state, new_state);
+/// 设置鼠标穿透状态，同步更新配置和窗口
+pub fn set_mouse_through(window: &dyn WindowController, state: &AppState, enabled: bool) {
+    // 更新配置文件
+    state.update_config(ConfigUpdate::MouseThrough(enabled));
 
     // 设置窗口鼠标穿透状态
-    if let Err(e) = window.set_ignore_cursor_events(new_state) {
-        println!("Failed to set ignore cursor events: {:?}", e);
+    if let Err(e) = window.set_mouse_through(enabled) {
+        warn!("Failed to set ignore cursor events: {:?}", e);
     } else {
-        println!("Successfully set ignore cursor events to: {}", new_state);
+        debug!("Successfully set ignore cursor events to: {}", enabled);
     }
 
-    // 重新构建菜单以确保状态更新
-    update_tray_menu(app, state, new_state, *state.auto_accept.lock().unwrap(), *state.auto_hide.lock().unwrap());
-
-    println!("Mouse through set to: {}", new_state);
+    info!("Mouse through set to: {}", enabled);
 }
 
-/// 处理自动接受菜单事件
-fn handle_auto_accept_event(app: &tauri::AppHandle, state: &AppState) {
-    println!("auto accept menu item was clicked");
+/// 设置自动接受状态
+pub fn set_auto_accept(state: &AppState, enabled: bool) {
+    // 更新配置文件
+    state.update_config(ConfigUpdate::AutoAccept(enabled));
 
-    // 获取当前状态并切换
-    let mut current_state = state.auto_accept.lock().unwrap();
-    let new_state = !*current_state;
-    *current_state = new_state;
+    info!("Auto accept set to: {}", enabled);
+}
 
+/// 设置自动隐藏状态
+pub fn set_auto_hide(state: &AppState, enabled: bool) {
     // 更新配置文件
-    state.config.lock().unwrap().update_auto_accept(new_state);
+    state.update_config(ConfigUpdate::AutoHide(enabled));
+
+    info!("Auto hide set to: {}", enabled);
+}
+
+/// 设置Discord状态同步开关
+///
+/// 同步任务会在收到事件后按新配置连接或断开 Discord。
+pub fn set_discord_presence(app: &tauri::AppHandle, state: &AppState, enabled: bool) {
+    state.update_config(ConfigUpdate::DiscordEnabled(enabled));
 
-    println!("Auto accept set to: {}", new_state);
+    info!("Discord presence set to: {}", enabled);
 
-    // 重新构建菜单以确保状态更新
-    update_tray_menu(app, state, *state.mouse_through.lock().unwrap(), new_state, *state.auto_hide.lock().unwrap());
+    events::emit(app, state, "discord-presence-changed", enabled);
 }
 
-/// 处理自动隐藏菜单事件
-fn handle_auto_hide_event(app: &tauri::AppHandle, state: &AppState) {
-    println!("auto hide menu item was clicked");
+/// 处理开机自启菜单事件
+fn handle_autostart_event(app: &tauri::AppHandle, state: &AppState) {
+    debug!("autostart menu item was clicked");
 
-    // 获取当前状态并切换
-    let mut current_state = state.auto_hide.lock().unwrap();
-    let new_state = !*current_state;
-    *current_state = new_state;
+    let new_state = !state.read(|snapshot| snapshot.config.autostart);
+    set_autostart(app, state, new_state);
+}
 
-    // 更新配置文件
-    state.config.lock().unwrap().update_auto_hide(new_state);
+/// 设置开机自启，注册成功后才保存到配置
+///
+/// 注册失败时配置不变，需要手动恢复菜单项的勾选状态。
+pub fn set_autostart(app: &tauri::AppHandle, state: &AppState, enabled: bool) {
+    if let Err(e) = autostart::set_enabled(enabled) {
+        warn!("设置开机自启失败: {}", e);
+        state.read(|snapshot| update_tray_menu(app, &snapshot.config));
+        return;
+    }
+    state.update_config(ConfigUpdate::Autostart(enabled));
 
-    println!("Auto hide set to: {}", new_state);
+    info!("Autostart set to: {}", enabled);
+}
+
+/// 处理配置档案菜单事件
+fn handle_profile_event(app: &tauri::AppHandle, state: &AppState, key: &str) {
+    debug!("profile menu item was clicked: {}", key);
+
+    let (puuid, name) = state.read(|snapshot| {
+        let profiles = &snapshot.config.profiles;
+        match profiles.accounts.get(key) {
+            Some(profile) => (Some(key.to_string()), profile.name.clone()),
+            None => (None, profiles.default.name.clone()),
+        }
+    });
+    profiles::switch_profile(app, state, puuid, &name);
+}
+
+/// 处理重置会话统计菜单事件
+fn handle_reset_session_event(app: &tauri::AppHandle, state: &AppState) {
+    debug!("reset session menu item was clicked");
+
+    state.dispatch(StateAction::Session(SessionAction::Reset));
+    publish_summary(app, state);
+}
+
+/// 处理打开日志文件夹菜单事件
+fn handle_open_log_folder_event(app: &tauri::AppHandle) {
+    debug!("open log folder menu item was clicked");
+
+    let result = logging::log_dir()
+        .map_err(|e| e.to_string())
+        .and_then(|directory| {
+            app.opener()
+                .open_path(directory.to_string_lossy(), None::<&str>)
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("打开日志文件夹失败: {}", e);
+    }
+}
+
+/// 处理导出诊断信息菜单事件，导出到日志目录后在文件管理器中显示
+fn handle_export_diagnostics_event(app: &tauri::AppHandle, state: &AppState) {
+    debug!("export diagnostics menu item was clicked");
+
+    let app = app.clone();
+    let state = state.clone();
+    tauri::async_runtime::spawn(async move {
+        let path = match diagnostics::default_path() {
+            Ok(path) => path,
+            Err(e) => {
+                warn!("导出诊断包失败: {}", e);
+                return;
+            }
+        };
+        match diagnostics::export(&state, &path).await {
+            Ok(()) => {
+                if let Err(e) = app.opener().reveal_item_in_dir(&path) {
+                    warn!("显示诊断包失败: {}", e);
+                }
+            }
+            Err(e) => warn!("导出诊断包失败: {}", e),
+        }
+    });
+}
+
+/// 构建托盘菜单
+fn build_tray_menu<M: Manager<Wry>>(manager: &M, config: &AppConfig) -> tauri::Result<Menu<Wry>> {
+    let quit_item = MenuItem::with_id(manager, "quit", "退出", true, None::<&str>)?;
+    let mouse_through_item = CheckMenuItem::with_id(
+        manager,
+        "mouse_through",
+        "鼠标穿透",
+        true,
+        config.mouse_through,
+        None::<&str>,
+    )?;
+    let auto_accept_item = CheckMenuItem::with_id(
+        manager,
+        "auto_accept",
+        "自动接受",
+        true,
+        config.auto_accept(),
+        None::<&str>,
+    )?;
+    let auto_hide_item = CheckMenuItem::with_id(
+        manager,
+        "auto_hide",
+        "自动隐藏",
+        true,
+        config.auto_hide,
+        None::<&str>,
+    )?;
+    let discord_item = CheckMenuItem::with_id(
+        manager,
+        "discord_presence",
+        "Discord状态",
+        true,
+        config.discord.enabled,
+        None::<&str>,
+    )?;
+    let autostart_item = CheckMenuItem::with_id(
+        manager,
+        "autostart",
+        "开机自启",
+        true,
+        config.autostart,
+        None::<&str>,
+    )?;
+    let profile_menu = build_profile_menu(manager, config)?;
+    let reset_session_item = MenuItem::with_id(manager, "reset_session", "重置会话统计", true, None::<&str>)?;
+    let open_log_folder_item = MenuItem::with_id(manager, "open_log_folder", "打开日志文件夹", true, None::<&str>)?;
+    let export_diagnostics_item = MenuItem::with_id(manager, "export_diagnostics", "导出诊断信息", true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(manager)?;
+    Menu::with_items(
+        manager,
+        &[
+            &mouse_through_item,
+            &auto_accept_item,
+            &auto_hide_item,
+            &discord_item,
+            &autostart_item,
+            &profile_menu,
+            &separator,
+            &reset_session_item,
+            &open_log_folder_item,
+            &export_diagnostics_item,
+            &quit_item,
+        ],
+    )
+}
+
+/// 构建配置档案子菜单，勾选当前使用的档案
+fn build_profile_menu<M: Manager<Wry>>(manager: &M, config: &AppConfig) -> tauri::Result<Submenu<Wry>> {
+    let profiles = &config.profiles;
+    let active_name = &profiles.active_profile().name;
+
+    let mut items = vec![CheckMenuItem::with_id(
+        manager,
+        format!("{}default", PROFILE_MENU_PREFIX),
+        &profiles.default.name,
+        true,
+        profiles.active.is_none(),
+        None::<&str>,
+    )?];
+    for (puuid, profile) in &profiles.accounts {
+        items.push(CheckMenuItem::with_id(
+            manager,
+            format!("{}{}", PROFILE_MENU_PREFIX, puuid),
+            &profile.name,
+            true,
+            profiles.active.as_deref() == Some(puuid.as_str()),
+            None::<&str>,
+        )?);
+    }
 
-    // 重新构建菜单以确保状态更新
-    update_tray_menu(app, state, *state.mouse_through.lock().unwrap(), *state.auto_accept.lock().unwrap(), new_state);
+    let item_refs: Vec<&dyn IsMenuItem<Wry>> = items.iter().map(|item| item as &dyn IsMenuItem<Wry>).collect();
+    Submenu::with_items(manager, format!("配置档案: {}", active_name), true, &item_refs)
 }
 
-/// 更新托盘菜单
-fn update_tray_menu(
-    app: &tauri::AppHandle,
-    _state: &AppState,
-    mouse_through_state: bool,
-    auto_accept_state: bool,
-    auto_hide_state: bool,
-) {
+/// 按配置重新构建托盘菜单
+///
+/// 由 `state_sync` 任务在开关或配置档案变化时调用。
+pub fn update_tray_menu(app: &tauri::AppHandle, config: &AppConfig) {
     if let Some(tray) = app.tray_by_id("main") {
-        if let Ok(quit_item_new) = MenuItem::with_id(app, "quit", "退出", true, None::<&str>) {
-            if let Ok(mouse_through_item_new) = CheckMenuItem::with_id(
-                app,
-                "mouse_through",
-                "鼠标穿透",
-                true,
-                mouse_through_state,
-                None::<&str>,
-            ) {
-                if let Ok(auto_accept_item_new) = CheckMenuItem::with_id(
-                    app,
-                    "auto_accept",
-                    "自动接受",
-                    true,
-                    auto_accept_state,
-                    None::<&str>,
-                ) {
-                    if let Ok(auto_hide_item_new) = CheckMenuItem::with_id(
-                        app,
-                        "auto_hide",
-                        "自动隐藏",
-                        true,
-                        auto_hide_state,
-                        None::<&str>,
-                    ) {
-                        if let Ok(new_menu) = Menu::with_items(
-                            app,
-                            &[&mouse_through_item_new, &auto_accept_item_new, &auto_hide_item_new, &quit_item_new],
-                        ) {
-                            if let Err(e) = tray.set_menu(Some(new_menu)) {
-                                println!("Failed to update tray menu: {:?}", e);
-                            } else {
-                                println!("Successfully updated tray menu");
-                            }
-                        }
-                    }
+        match build_tray_menu(app, config) {
+            Ok(new_menu) => {
+                if let Err(e) = tray.set_menu(Some(new_menu)) {
+                    warn!("Failed to update tray menu: {:?}", e);
+                } else {
+                    debug!("Successfully updated tray menu");
                 }
             }
+            Err(e) => warn!("Failed to build tray menu: {:?}", e),
+        }
+    }
+}
+
+/// 更新托盘提示文本
+pub fn update_tray_tooltip(app: &tauri::AppHandle, tooltip: &str) {
+    if let Some(tray) = app.tray_by_id("main") {
+        if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+            warn!("Failed to update tray tooltip: {:?}", e);
         }
     }
 }
\ No newline at end of file
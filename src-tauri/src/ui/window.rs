@@ -1,5 +1,7 @@
 use crate::core::AppState;
+use crate::utils::{config::ConfigUpdate, launch};
 use tauri::{Manager, PhysicalPosition};
+use tracing::{debug, info, warn};
 
 /// 设置窗口
 pub fn setup_window(
@@ -12,16 +14,16 @@ pub fn setup_window(
     window.set_skip_taskbar(true).unwrap();
 
     // 从配置加载初始状态
-    let config = app_state.config.lock().unwrap();
+    let config = app_state.snapshot().config;
     let mouse_through_state = config.mouse_through;
     let window_visible = config.window_visible;
 
     // 设置窗口位置
     let position = PhysicalPosition::new(config.window_position.x, config.window_position.y);
     if let Err(e) = window.set_position(position) {
-        println!("设置窗口位置失败: {}", e);
+        warn!("设置窗口位置失败: {}", e);
     } else {
-        println!(
+        debug!(
             "窗口位置设置为: ({}, {})",
             config.window_position.x, config.window_position.y
         );
@@ -29,15 +31,66 @@ pub fn setup_window(
 
     // 设置鼠标穿透状态
     window.set_ignore_cursor_events(mouse_through_state).unwrap();
-    drop(config); // 释放锁
 
-    // 根据配置决定是否显示窗口
-    if window_visible {
+    // 根据配置决定是否显示窗口，最小化启动时先留在托盘
+    if window_visible && !launch::options().minimized {
         window.show().unwrap();
+    } else if window_visible {
+        info!("最小化启动，窗口保持隐藏状态");
     } else {
         // 如果配置为隐藏，则不显示窗口
-        println!("根据配置，窗口保持隐藏状态");
+        info!("根据配置，窗口保持隐藏状态");
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// 主窗口操作，界面模式下由 Tauri 实现，无界面模式下不执行任何操作
+pub trait WindowController: Send + Sync {
+    fn is_visible(&self) -> bool;
+    fn set_visible(&self, visible: bool) -> Result<(), String>;
+    fn focus(&self) -> Result<(), String>;
+    fn set_mouse_through(&self, enabled: bool) -> Result<(), String>;
+    fn set_position(&self, x: i32, y: i32) -> Result<(), String>;
+}
+
+impl WindowController for tauri::AppHandle {
+    fn is_visible(&self) -> bool {
+        self.get_webview_window("main")
+            .and_then(|window| window.is_visible().ok())
+            .unwrap_or(false)
+    }
+
+    fn set_visible(&self, visible: bool) -> Result<(), String> {
+        let window = self.get_webview_window("main").ok_or("找不到主窗口")?;
+        let result = if visible { window.show() } else { window.hide() };
+        result.map_err(|e| e.to_string())
+    }
+
+    fn focus(&self) -> Result<(), String> {
+        let window = self.get_webview_window("main").ok_or("找不到主窗口")?;
+        window.set_focus().map_err(|e| e.to_string())
+    }
+
+    fn set_mouse_through(&self, enabled: bool) -> Result<(), String> {
+        let window = self.get_webview_window("main").ok_or("找不到主窗口")?;
+        window.set_ignore_cursor_events(enabled).map_err(|e| e.to_string())
+    }
+
+    fn set_position(&self, x: i32, y: i32) -> Result<(), String> {
+        let window = self.get_webview_window("main").ok_or("找不到主窗口")?;
+        window.set_position(PhysicalPosition::new(x, y)).map_err(|e| e.to_string())
+    }
+}
+
+/// 显示或隐藏主窗口并保存可见性，显示时同时聚焦
+pub fn set_window_visible(window: &dyn WindowController, state: &AppState, visible: bool) {
+    let result = window
+        .set_visible(visible)
+        .and_then(|_| if visible { window.focus() } else { Ok(()) });
+    if let Err(e) = result {
+        warn!("切换窗口显示失败: {}", e);
+        return;
+    }
+    state.update_config(ConfigUpdate::WindowVisible(visible));
+}
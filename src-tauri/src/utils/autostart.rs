@@ -0,0 +1,193 @@
+use super::launch;
+use tracing::info;
+
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+#[cfg(target_os = "windows")]
+use std::process::Command as WinCommand;
+
+/// 开机自启时附加的启动参数，启动后不显示窗口
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+/// XDG 自启动目录下的文件名
+#[cfg(target_os = "linux")]
+const DESKTOP_FILE: &str = "micept.desktop";
+
+/// 当前用户的 Run 注册表项和值名称
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+#[cfg(target_os = "windows")]
+const RUN_VALUE: &str = "micept";
+
+/// 开机自启时执行的命令：当前可执行文件、`--minimized`，以及本次运行指定的配置文件和无界面模式
+fn launch_command() -> Result<Vec<String>, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("获取程序路径失败: {}", e))?;
+    let mut command = vec![exe.to_string_lossy().to_string(), MINIMIZED_ARG.to_string()];
+    if launch::options().headless {
+        command.push("--headless".to_string());
+    }
+    if let Some(path) = &launch::options().config_path {
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.clone());
+        command.push("--config".to_string());
+        command.push(path.to_string_lossy().to_string());
+    }
+    Ok(command)
+}
+
+/// 按配置注册或取消开机自启
+///
+/// 开启时每次都重新写入，程序移动位置后启动一次即可更新路径。
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    let command = launch_command()?;
+    platform::set_enabled(enabled, &command)?;
+    info!(enabled, "开机自启已更新");
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+
+    /// XDG 自启动目录，遵循 `XDG_CONFIG_HOME`
+    fn autostart_dir() -> Result<PathBuf, String> {
+        Ok(dirs::config_dir().ok_or("无法获取配置目录")?.join("autostart"))
+    }
+
+    pub fn set_enabled(enabled: bool, command: &[String]) -> Result<(), String> {
+        let directory = autostart_dir()?;
+        if enabled {
+            register(&directory, command)
+        } else {
+            unregister(&directory)
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+
+    fn reg(args: &[&str]) -> Result<std::process::Output, String> {
+        WinCommand::new("reg")
+            .args(args)
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW
+            .output()
+            .map_err(|e| format!("执行reg命令失败: {}", e))
+    }
+
+    pub fn set_enabled(enabled: bool, command: &[String]) -> Result<(), String> {
+        if !enabled {
+            if is_registered() {
+                let output = reg(&["delete", RUN_KEY, "/v", RUN_VALUE, "/f"])?;
+                if !output.status.success() {
+                    return Err(format!("删除开机自启失败: {}", String::from_utf8_lossy(&output.stderr)));
+                }
+            }
+            return Ok(());
+        }
+
+        let command_line = command
+            .iter()
+            .map(|arg| format!("\"{}\"", arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let output = reg(&["add", RUN_KEY, "/v", RUN_VALUE, "/t", "REG_SZ", "/d", &command_line, "/f"])?;
+        if !output.status.success() {
+            return Err(format!("写入开机自启失败: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+        Ok(())
+    }
+
+    fn is_registered() -> bool {
+        reg(&["query", RUN_KEY, "/v", RUN_VALUE]).is_ok_and(|output| output.status.success())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+mod platform {
+    pub fn set_enabled(_enabled: bool, _command: &[String]) -> Result<(), String> {
+        Err("当前系统不支持开机自启".to_string())
+    }
+}
+
+/// 自启动 `.desktop` 文件内容，参数中的空格和特殊字符按桌面文件规范加引号
+#[cfg(target_os = "linux")]
+fn desktop_entry(command: &[String]) -> String {
+    let exec = command
+        .iter()
+        .map(|arg| {
+            if arg.chars().any(|c| c.is_whitespace() || "\"'\\`$%".contains(c)) {
+                let escaped = arg
+                    .replace('\\', "\\\\\\\\")
+                    .replace('"', "\\\\\"")
+                    .replace('`', "\\\\`")
+                    .replace('$', "\\\\$");
+                format!("\"{}\"", escaped.replace('%', "%%"))
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "[Desktop Entry]\nType=Application\nName=micept\nComment=英雄联盟客户端助手\nExec={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+        exec
+    )
+}
+
+/// 在自启动目录中写入 `.desktop` 文件
+#[cfg(target_os = "linux")]
+fn register(directory: &Path, command: &[String]) -> Result<(), String> {
+    fs::create_dir_all(directory).map_err(|e| format!("创建自启动目录失败: {}", e))?;
+    fs::write(directory.join(DESKTOP_FILE), desktop_entry(command))
+        .map_err(|e| format!("写入自启动文件失败: {}", e))
+}
+
+/// 删除自启动目录中的 `.desktop` 文件，文件不存在时不报错
+#[cfg(target_os = "linux")]
+fn unregister(directory: &Path) -> Result<(), String> {
+    match fs::remove_file(directory.join(DESKTOP_FILE)) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("删除自启动文件失败: {}", e)),
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    fn is_registered(directory: &Path) -> bool {
+        directory.join(DESKTOP_FILE).is_file()
+    }
+
+    #[test]
+    fn register_writes_desktop_entry() {
+        let directory = temp_dir("autostart-register").join("autostart");
+        let command = vec!["/opt/micept/micept".to_string(), MINIMIZED_ARG.to_string()];
+
+        register(&directory, &command).unwrap();
+        assert!(is_registered(&directory));
+        let content = fs::read_to_string(directory.join(DESKTOP_FILE)).unwrap();
+        assert!(content.starts_with("[Desktop Entry]\n"));
+        assert!(content.contains("\nExec=/opt/micept/micept --minimized\n"));
+
+        unregister(&directory).unwrap();
+        assert!(!is_registered(&directory));
+        unregister(&directory).unwrap();
+        let _ = fs::remove_dir_all(directory.parent().unwrap());
+    }
+
+    #[test]
+    fn desktop_entry_quotes_paths_with_spaces() {
+        let command = vec!["/home/me/My Apps/micept".to_string(), "--config".to_string(), "/tmp/a$b.json".to_string()];
+        let content = desktop_entry(&command);
+        assert!(content.contains("Exec=\"/home/me/My Apps/micept\" --config \"/tmp/a\\\\$b.json\"\n"), "{}", content);
+    }
+}
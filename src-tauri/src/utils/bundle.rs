@@ -0,0 +1,310 @@
+use super::config::{AppConfig, ChampionLists, RunePage};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// 配置包的格式标识
+pub const BUNDLE_FORMAT: &str = "micept-config";
+
+/// 配置包格式版本，主版本号不同的文件无法导入
+pub const BUNDLE_VERSION: &str = "1.0";
+
+/// 密钥、可能带有令牌的 Webhook 地址和账号标识，只有明确选择时才导出
+const PRIVATE_FIELDS: &[&str] = &[
+    "/mqtt/password",
+    "/webhooks/secret",
+    "/webhooks/ready_check_accepted",
+    "/webhooks/game_start",
+    "/webhooks/game_end",
+    "/profiles/active",
+    "/profiles/accounts",
+];
+
+/// 可移植的配置包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    pub format: String,
+    pub version: String,
+    #[serde(default)]
+    pub exported_at: String,
+    /// 完整的应用配置，导入时与现有配置合并
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<Value>,
+    /// 可在队友间共享的选用/禁用英雄和符文预设
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<Preset>,
+}
+
+/// 选用/禁用英雄和符文预设
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preset {
+    pub champion_lists: ChampionLists,
+    pub rune_pages: Vec<RunePage>,
+}
+
+/// 导入前的预览结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub changes: Vec<ConfigChange>,
+    /// 类型不正确而被忽略的字段
+    pub rejected: Vec<String>,
+    /// 合并后的配置，确认导入时直接使用
+    #[serde(skip)]
+    pub config: AppConfig,
+}
+
+/// 一项配置变化，`path` 为以 `/` 分隔的字段路径
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChange {
+    pub path: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// 生成配置包
+///
+/// 预设取自当前使用的配置档案。`include_private` 为 false 时不包含 `PRIVATE_FIELDS`，
+/// 导入这样的配置包不会改动对方的密钥和账号档案。
+pub fn create_bundle(
+    config: &AppConfig,
+    include_config: bool,
+    include_preset: bool,
+    include_private: bool,
+) -> Result<ConfigBundle, String> {
+    let config_value = if include_config {
+        let mut value = config.persisted_value().map_err(|e| format!("序列化配置失败: {}", e))?;
+        if !include_private {
+            remove_private_fields(&mut value);
+        }
+        Some(value)
+    } else {
+        None
+    };
+    let preset = include_preset.then(|| {
+        let profile = config.profiles.active_profile();
+        Preset {
+            champion_lists: profile.champion_lists.clone(),
+            rune_pages: profile.rune_pages.clone(),
+        }
+    });
+
+    Ok(ConfigBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION.to_string(),
+        exported_at: chrono::Local::now().to_rfc3339(),
+        config: config_value,
+        preset,
+    })
+}
+
+fn remove_private_fields(value: &mut Value) {
+    for pointer in PRIVATE_FIELDS {
+        let (parent, key) = pointer.rsplit_once('/').unwrap_or_default();
+        if let Some(Value::Object(parent)) = value.pointer_mut(parent) {
+            parent.remove(key);
+        }
+    }
+}
+
+/// 写入配置包文件
+pub fn write_bundle(path: &Path, bundle: &ConfigBundle) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(bundle).map_err(|e| format!("序列化配置包失败: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("写入文件失败: {}", e))
+}
+
+/// 读取并校验配置包文件
+pub fn read_bundle(path: &Path) -> Result<ConfigBundle, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    parse_bundle(&content)
+}
+
+/// 解析配置包，格式标识或主版本号不符时返回错误
+pub fn parse_bundle(content: &str) -> Result<ConfigBundle, String> {
+    let value: Value = serde_json::from_str(content).map_err(|e| format!("配置包不是有效的JSON: {}", e))?;
+    if value["format"] != BUNDLE_FORMAT {
+        return Err("不是micept配置包".to_string());
+    }
+
+    let version = value["version"].as_str().unwrap_or_default();
+    if major_version(version) != major_version(BUNDLE_VERSION) {
+        return Err(format!(
+            "不支持的配置包版本: {}，当前支持 {}.x",
+            version,
+            major_version(BUNDLE_VERSION).unwrap_or_default()
+        ));
+    }
+
+    serde_json::from_value(value).map_err(|e| format!("配置包内容无效: {}", e))
+}
+
+fn major_version(version: &str) -> Option<u32> {
+    version.split('.').next()?.parse().ok()
+}
+
+/// 将配置包与当前配置合并并列出变化，不修改当前配置
+///
+/// 配置包中没有的字段保持不变，预设会替换当前档案的英雄列表和符文页。
+/// 比较的是配置文件中的值，不包含启动参数的覆盖。
+pub fn preview_import(bundle: &ConfigBundle, current: &AppConfig) -> Result<ImportPreview, String> {
+    let before = current.persisted_value().map_err(|e| format!("序列化配置失败: {}", e))?;
+
+    let mut merged = before.clone();
+    if let Some(incoming) = &bundle.config {
+        if !incoming.is_object() {
+            return Err("配置包中的配置不是JSON对象".to_string());
+        }
+        merge_values(&mut merged, incoming.clone());
+    }
+    let (mut config, _, rejected) = AppConfig::parse(&merged.to_string())?;
+    config.check_writable()?;
+
+    if let Some(preset) = &bundle.preset {
+        let profile = config.profiles.active_profile_mut();
+        profile.champion_lists = preset.champion_lists.clone();
+        profile.rune_pages = preset.rune_pages.clone();
+    }
+
+    let after = config.persisted_value().map_err(|e| format!("序列化配置失败: {}", e))?;
+    Ok(ImportPreview {
+        changes: diff_values(&before, &after),
+        rejected,
+        config,
+    })
+}
+
+/// 递归合并 JSON 对象，`incoming` 中的值优先
+fn merge_values(base: &mut Value, incoming: Value) {
+    match (base, incoming) {
+        (Value::Object(base), Value::Object(incoming)) => {
+            for (key, value) in incoming {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, incoming) => *base = incoming,
+    }
+}
+
+/// 比较两份配置，数组作为整体比较
+fn diff_values(before: &Value, after: &Value) -> Vec<ConfigChange> {
+    let mut before_fields = Map::new();
+    let mut after_fields = Map::new();
+    flatten("", before, &mut before_fields);
+    flatten("", after, &mut after_fields);
+
+    let paths: BTreeSet<&String> = before_fields.keys().chain(after_fields.keys()).collect();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let before = before_fields.get(path).cloned().unwrap_or(Value::Null);
+            let after = after_fields.get(path).cloned().unwrap_or(Value::Null);
+            (before != after).then(|| ConfigChange {
+                path: path.clone(),
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+fn flatten(prefix: &str, value: &Value, fields: &mut Map<String, Value>) {
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (key, value) in object {
+                flatten(&format!("{}/{}", prefix, key), value, fields);
+            }
+        }
+        _ => {
+            fields.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::config::AccountProfile;
+
+    fn bundle_json(version: &str, config: Value) -> String {
+        serde_json::json!({ "format": BUNDLE_FORMAT, "version": version, "config": config }).to_string()
+    }
+
+    #[test]
+    fn other_major_version_is_rejected() {
+        let error = parse_bundle(&bundle_json("2.0", serde_json::json!({}))).unwrap_err();
+        assert!(error.contains("不支持的配置包版本: 2.0"), "{}", error);
+        assert!(parse_bundle(&bundle_json("", serde_json::json!({}))).is_err());
+        assert!(parse_bundle(r#"{"format":"other","version":"1.0"}"#).is_err());
+    }
+
+    #[test]
+    fn newer_minor_version_is_accepted() {
+        let bundle = parse_bundle(&bundle_json("1.7", serde_json::json!({ "auto_hide": true }))).unwrap();
+        assert_eq!(bundle.version, "1.7");
+        assert_eq!(bundle.config.unwrap()["auto_hide"], true);
+    }
+
+    #[test]
+    fn preview_lists_changes_and_rejected_fields() {
+        let content = bundle_json(
+            "1.0",
+            serde_json::json!({ "auto_hide": true, "api": { "port": 18000 }, "mouse_through": "yes" }),
+        );
+        let mut bundle = parse_bundle(&content).unwrap();
+        bundle.preset = Some(Preset {
+            champion_lists: ChampionLists { pick: vec![1, 2], ban: vec![3] },
+            rune_pages: Vec::new(),
+        });
+        let current = AppConfig::default();
+        let preview = preview_import(&bundle, &current).unwrap();
+
+        let paths: Vec<&str> = preview.changes.iter().map(|change| change.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/api/port",
+                "/auto_hide",
+                "/profiles/default/champion_lists/ban",
+                "/profiles/default/champion_lists/pick",
+            ]
+        );
+        assert_eq!(preview.changes[0].before, 17321);
+        assert_eq!(preview.changes[0].after, 18000);
+        assert_eq!(preview.rejected, vec!["mouse_through"]);
+        assert!(preview.config.auto_hide);
+        assert!(preview.config.mouse_through);
+    }
+
+    #[test]
+    fn private_fields_are_only_exported_on_request() {
+        let mut config = AppConfig::default();
+        config.mqtt.password = "mqtt-password".to_string();
+        config.webhooks.secret = Some("hook-secret".to_string());
+        config.webhooks.game_end = vec!["https://example.com/hook/token".to_string()];
+        config.profiles.accounts.insert("puuid-a".to_string(), AccountProfile::default());
+        config.profiles.active = Some("puuid-a".to_string());
+
+        let shared = create_bundle(&config, true, false, false).unwrap();
+        let content = serde_json::to_string(&shared).unwrap();
+        for secret in ["mqtt-password", "hook-secret", "example.com", "puuid-a"] {
+            assert!(!content.contains(secret), "{}", secret);
+        }
+
+        // 导入不含私密字段的配置包时保留现有值
+        let preview = preview_import(&shared, &config).unwrap();
+        assert!(preview.changes.is_empty());
+        assert_eq!(preview.config.mqtt.password, "mqtt-password");
+
+        let full = create_bundle(&config, true, false, true).unwrap();
+        let config_value = full.config.unwrap();
+        assert_eq!(config_value["mqtt"]["password"], "mqtt-password");
+        assert!(config_value["profiles"]["accounts"]["puuid-a"].is_object());
+    }
+}
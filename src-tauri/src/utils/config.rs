@@ -1,40 +1,365 @@
+use chrono::NaiveTime;
+use super::launch::{self, ConfigOverride};
+use super::redact;
+use crate::lol;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 当前配置结构的版本号，没有 `version` 字段的旧配置视为版本 0
+pub const CONFIG_VERSION: u32 = 3;
+
+/// 将配置从某个版本升级到下一个版本
+type Migration = fn(&mut Map<String, Value>);
+
+/// 迁移链，第 i 项负责从版本 i 升级到版本 i + 1
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1, migrate_v1_to_v2, migrate_v2_to_v3];
+
+/// 应用配置，缺失的字段使用默认值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
+    pub version: u32,
     pub window_position: WindowPosition,
     pub mouse_through: bool,
-    pub auto_accept: bool,
     pub auto_hide: bool,
     pub window_visible: bool,
+    /// 登录系统时自动启动，启动后不显示窗口
+    pub autostart: bool,
+    pub notifications: NotificationConfig,
+    pub webhooks: WebhookConfig,
+    pub api: ApiConfig,
+    pub overlay: OverlayConfig,
+    pub discord: DiscordConfig,
+    pub mqtt: MqttConfig,
+    pub profiles: ProfilesConfig,
+    /// 日志级别：error、warn、info、debug 或 trace
+    pub log_level: String,
+    /// 本版本不认识的字段，原样写回以免降级后丢失数据
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+    /// 被启动参数覆盖的字段及其在配置文件中的原值，保存时写回原值
+    #[serde(skip)]
+    pub overridden: BTreeMap<String, Value>,
+    /// 作用于当前档案的启动参数覆盖，例如 `--auto-accept`，切换档案后对新档案重新应用
+    #[serde(skip)]
+    pub profile_overrides: Vec<ConfigOverride>,
+    /// 配置文件来自更新版本的 micept，本次运行不写入文件，避免用旧版本的结构覆盖新版本的设置
+    #[serde(skip)]
+    pub read_only: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct WindowPosition {
     pub x: i32,
     pub y: i32,
 }
 
+impl Default for WindowPosition {
+    fn default() -> Self {
+        Self { x: -400, y: 0 } // 默认右上角
+    }
+}
+
+/// 桌面通知配置，每种事件单独开关
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub ready_check: bool,
+    pub champ_select: bool,
+    pub pick_turn: bool,
+    pub game_start: bool,
+    pub sound_enabled: bool,
+    pub sound_file: Option<String>,
+    pub quiet_hours: QuietHours,
+}
+
+/// 免打扰时段，格式为 `HH:MM`，支持跨越午夜
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuietHours {
+    pub enabled: bool,
+    pub start: String,
+    pub end: String,
+}
+
+/// 本地 HTTP 控制接口配置，默认关闭
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 17321,
+        }
+    }
+}
+
+/// OBS 叠加层服务配置，默认关闭
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlayConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for OverlayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 17322,
+        }
+    }
+}
+
+/// 账号配置档案，按召唤师 puuid 区分
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountProfile {
+    pub name: String,
+    pub auto_accept: bool,
+    pub champion_lists: ChampionLists,
+    pub rune_pages: Vec<RunePage>,
+}
+
+impl Default for AccountProfile {
+    fn default() -> Self {
+        Self {
+            name: "默认".to_string(),
+            auto_accept: true,
+            champion_lists: ChampionLists::default(),
+            rune_pages: Vec::new(),
+        }
+    }
+}
+
+/// 预设的选用和禁用英雄，按优先级排列的英雄ID
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChampionLists {
+    pub pick: Vec<u32>,
+    pub ban: Vec<u32>,
+}
+
+/// 符文页，字段与 `/lol-perks/v1/pages` 一致
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunePage {
+    pub name: String,
+    pub primary_style_id: u32,
+    pub sub_style_id: u32,
+    pub selected_perk_ids: Vec<u32>,
+}
+
+/// 全部配置档案
+///
+/// `active` 为当前账号的 puuid，为 None 时使用默认档案。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfilesConfig {
+    pub active: Option<String>,
+    pub default: AccountProfile,
+    pub accounts: BTreeMap<String, AccountProfile>,
+}
+
+impl ProfilesConfig {
+    /// 当前使用的配置档案
+    pub fn active_profile(&self) -> &AccountProfile {
+        self.active
+            .as_ref()
+            .and_then(|puuid| self.accounts.get(puuid))
+            .unwrap_or(&self.default)
+    }
+
+    /// 当前使用的配置档案（可修改）
+    pub fn active_profile_mut(&mut self) -> &mut AccountProfile {
+        match self.active.as_ref().filter(|puuid| self.accounts.contains_key(*puuid)) {
+            Some(puuid) => self.accounts.get_mut(puuid).unwrap(),
+            None => &mut self.default,
+        }
+    }
+
+    /// 切换到指定账号的档案，首次出现的账号以默认档案为模板创建
+    ///
+    /// `puuid` 为 None 时切换回默认档案。
+    pub fn activate(&mut self, puuid: Option<&str>, name: &str) {
+        match puuid {
+            Some(puuid) => {
+                let default = &self.default;
+                self.accounts.entry(puuid.to_string()).or_insert_with(|| AccountProfile {
+                    name: name.to_string(),
+                    ..default.clone()
+                });
+                self.active = Some(puuid.to_string());
+            }
+            None => self.active = None,
+        }
+    }
+}
+
+/// Discord 状态同步配置，默认关闭
+///
+/// `client_id` 为在 Discord 开发者平台创建的应用ID，为空时不连接。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscordConfig {
+    pub enabled: bool,
+    pub client_id: String,
+}
+
+/// MQTT 发布配置，默认关闭
+///
+/// 状态以保留消息发布在 `topic_prefix` 下，用户名为空时不进行认证。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub enabled: bool,
+    pub broker_url: String,
+    pub username: String,
+    pub password: String,
+    pub topic_prefix: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            broker_url: "mqtt://127.0.0.1:1883".to_string(),
+            username: String::new(),
+            password: String::new(),
+            topic_prefix: "micept".to_string(),
+        }
+    }
+}
+
+/// Webhook 配置，每种事件可以配置多个地址
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub ready_check_accepted: Vec<String>,
+    pub game_start: Vec<String>,
+    pub game_end: Vec<String>,
+    /// 用于 HMAC-SHA256 签名请求体的密钥，为空时不签名
+    pub secret: Option<String>,
+    pub timeout_secs: u64,
+    pub max_retries: u32,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            ready_check_accepted: Vec::new(),
+            game_start: Vec::new(),
+            game_end: Vec::new(),
+            secret: None,
+            timeout_secs: 5,
+            max_retries: 3,
+        }
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            ready_check: true,
+            champ_select: false,
+            pick_turn: true,
+            game_start: false,
+            sound_enabled: false,
+            sound_file: None,
+            quiet_hours: QuietHours::default(),
+        }
+    }
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: "23:00".to_string(),
+            end: "08:00".to_string(),
+        }
+    }
+}
+
+impl QuietHours {
+    /// 判断给定时间是否处于免打扰时段，时间格式无效时视为不在时段内
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let (Ok(start), Ok(end)) = (
+            NaiveTime::parse_from_str(&self.start, "%H:%M"),
+            NaiveTime::parse_from_str(&self.end, "%H:%M"),
+        ) else {
+            return false;
+        };
+        if start <= end {
+            start <= time && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
-            window_position: WindowPosition { x: -400, y: 0 }, // 默认右上角
+            version: CONFIG_VERSION,
+            window_position: WindowPosition::default(),
             mouse_through: true,  // 默认开启鼠标穿透
-            auto_accept: true,
             auto_hide: false,     // 默认关闭自动隐藏
             window_visible: true,
+            autostart: false,
+            notifications: NotificationConfig::default(),
+            webhooks: WebhookConfig::default(),
+            api: ApiConfig::default(),
+            overlay: OverlayConfig::default(),
+            discord: DiscordConfig::default(),
+            mqtt: MqttConfig::default(),
+            profiles: ProfilesConfig::default(),
+            log_level: "info".to_string(),
+            extra: Map::new(),
+            overridden: BTreeMap::new(),
+            profile_overrides: Vec::new(),
+            read_only: false,
         }
     }
 }
 
 impl AppConfig {
+    /// 是否自动接受对局，取自当前配置档案
+    pub fn auto_accept(&self) -> bool {
+        self.profiles.active_profile().auto_accept
+    }
+
     /// 获取配置文件路径
+    ///
+    /// 优先使用 `--config` 指定的路径，便携模式下位于可执行文件旁，
+    /// 否则为系统配置目录下的 `micept/config.json`。
     pub fn config_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
-        let config_dir = dirs::config_dir()
-            .ok_or("无法获取配置目录")?
-            .join("micept");
+        let launch = launch::options();
+        if let Some(path) = &launch.config_path {
+            let path = std::path::absolute(path)?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            return Ok(path);
+        }
+
+        let config_dir = match launch.portable_dir() {
+            Some(dir) => dir,
+            None => dirs::config_dir().ok_or("无法获取配置目录")?.join("micept"),
+        };
         
         // 确保配置目录存在
         fs::create_dir_all(&config_dir)?;
@@ -43,85 +368,764 @@ impl AppConfig {
     }
 
     /// 从文件加载配置
+    ///
+    /// 旧版本配置会依次迁移到当前版本；无法解析的文件或被丢弃的字段
+    /// 会先备份到 `config.json.bak`，其余设置照常保留。
     pub fn load() -> Self {
+        let mut config = Self::load_file();
+        config.apply_overrides();
+        config
+    }
+
+    /// 从文件加载配置，不应用启动参数
+    fn load_file() -> Self {
         match Self::config_path() {
-            Ok(path) => {
-                if path.exists() {
-                    match fs::read_to_string(&path) {
-                        Ok(content) => {
-                            match serde_json::from_str::<AppConfig>(&content) {
-                                Ok(config) => {
-                                    println!("成功加载配置文件: {:?}", config);
-                                    return config;
-                                }
-                                Err(e) => {
-                                    println!("解析配置文件失败: {}, 使用默认配置", e);
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            println!("读取配置文件失败: {}, 使用默认配置", e);
-                        }
+            Ok(path) => Self::load_from(&path),
+            Err(e) => {
+                warn!("获取配置文件路径失败: {}, 使用默认配置", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// 从指定文件加载配置，迁移或丢弃了字段时写回该文件
+    fn load_from(path: &Path) -> Self {
+        if !path.exists() {
+            info!("配置文件不存在，使用默认配置");
+            return Self::default();
+        }
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("读取配置文件失败: {}, 使用默认配置", e);
+                return Self::default();
+            }
+        };
+
+        match Self::parse(&content) {
+            Ok((config, _, _)) if config.read_only => {
+                warn!(
+                    "配置文件版本{}高于当前支持的版本{}，本次运行不会保存配置修改，请升级 micept",
+                    config.version, CONFIG_VERSION
+                );
+                config
+            }
+            Ok((config, migrated_from, rejected)) => {
+                if !rejected.is_empty() {
+                    warn!("配置字段无效，已使用默认值: {}", rejected.join(", "));
+                    Self::backup(path);
+                }
+                if let Some(version) = migrated_from {
+                    info!("配置已从版本{}迁移到版本{}", version, config.version);
+                }
+                if migrated_from.is_some() || !rejected.is_empty() {
+                    let result = config
+                        .to_file_content()
+                        .map_err(Into::into)
+                        .and_then(|content| Self::write_to(path, &content));
+                    if let Err(e) = result {
+                        warn!("保存迁移后的配置失败: {}", e);
                     }
-                } else {
-                    println!("配置文件不存在，使用默认配置");
                 }
+                debug!("成功加载配置文件: {:?}", config);
+                config
             }
             Err(e) => {
-                println!("获取配置文件路径失败: {}, 使用默认配置", e);
+                warn!("解析配置文件失败: {}, 使用默认配置", e);
+                Self::backup(path);
+                Self::default()
             }
         }
-        
-        Self::default()
+    }
+
+    /// 解析配置内容
+    ///
+    /// 返回配置、迁移前的版本（未迁移时为 None）以及因类型错误被丢弃的字段，
+    /// 嵌套字段以点分隔，例如 `notifications.quiet_hours.start`。
+    /// 内容不是 JSON 对象或版本号无效时返回错误。
+    /// 版本高于当前版本时尽量读取，不认识的字段保留在 `extra` 中，并标记为 `read_only`。
+    /// 配置中的账号和密钥会登记到 `redact`。
+    pub fn parse(content: &str) -> Result<(Self, Option<u32>, Vec<String>), String> {
+        let mut fields = match serde_json::from_str::<Value>(content).map_err(|e| e.to_string())? {
+            Value::Object(fields) => fields,
+            _ => return Err("配置文件不是JSON对象".to_string()),
+        };
+
+        let version = match fields.get("version") {
+            None => 0,
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or_else(|| format!("无效的配置版本: {}", version))?,
+        };
+        let read_only = version > CONFIG_VERSION;
+        let migrated_from = (version < CONFIG_VERSION).then_some(version);
+        for migration in MIGRATIONS.iter().skip(version as usize) {
+            migration(&mut fields);
+        }
+        if migrated_from.is_some() {
+            fields.insert("version".to_string(), CONFIG_VERSION.into());
+        }
+
+        if let Ok(mut config) = serde_json::from_value::<Self>(Value::Object(fields.clone())) {
+            config.read_only = read_only;
+            config.register_secrets();
+            return Ok((config, migrated_from, Vec::new()));
+        }
+
+        // 整体解析失败时逐个合并字段，只丢弃无法解析的字段
+        let mut merged = serde_json::to_value(Self::default()).map_err(|e| e.to_string())?;
+        let mut rejected = Vec::new();
+        for (key, value) in fields {
+            merge_field(&mut merged, &format!("/{}", escape_pointer(&key)), value, &mut rejected);
+        }
+        let mut config: Self = serde_json::from_value(merged).map_err(|e| e.to_string())?;
+        config.read_only = read_only;
+        config.register_secrets();
+        Ok((config, migrated_from, rejected))
+    }
+
+    /// 登记需要在日志中隐藏的内容：账号 puuid、档案名称、MQTT 密码和 Webhook 密钥
+    pub fn register_secrets(&self) {
+        for (puuid, profile) in &self.profiles.accounts {
+            redact::register(puuid);
+            if profile.name != lol::UNKNOWN_SUMMONER_NAME {
+                redact::register(&profile.name);
+            }
+        }
+        redact::register(&self.mqtt.password);
+        if let Some(secret) = &self.webhooks.secret {
+            redact::register(secret);
+        }
+    }
+
+    /// 应用启动参数中的配置覆盖
+    ///
+    /// 未知字段或无法解析的值会被忽略并提示，被覆盖字段的原值记录在 `overridden` 中。
+    pub fn apply_overrides(&mut self) {
+        self.apply_override_list(&launch::options().overrides);
+    }
+
+    fn apply_override_list(&mut self, overrides: &[ConfigOverride]) {
+        if overrides.is_empty() {
+            return;
+        }
+        let Ok(mut value) = serde_json::to_value(&*self) else {
+            return;
+        };
+        let mut overridden = std::mem::take(&mut self.overridden);
+        let mut profile_overrides = std::mem::take(&mut self.profile_overrides);
+
+        for item in overrides {
+            let pointer = self.resolve_pointer(&item.pointer);
+            let Some(previous) = value.pointer(&pointer).cloned() else {
+                warn!("未知的配置项: {}", item.source);
+                continue;
+            };
+            let accepted = item.candidates().into_iter().find(|candidate| {
+                *value.pointer_mut(&pointer).unwrap() = candidate.clone();
+                serde_json::from_value::<Self>(value.clone()).is_ok()
+            });
+            match accepted {
+                Some(candidate) => {
+                    info!("启动参数覆盖配置: {} = {}", pointer, candidate);
+                    if pointer != item.pointer {
+                        profile_overrides.push(item.clone());
+                    }
+                    overridden.entry(pointer).or_insert(previous);
+                }
+                None => {
+                    warn!("配置项{}的值无效: {}", pointer, item.value);
+                    *value.pointer_mut(&pointer).unwrap() = previous;
+                }
+            }
+        }
+
+        if let Ok(mut config) = serde_json::from_value::<Self>(value) {
+            config.read_only = self.read_only;
+            *self = config;
+        }
+        self.overridden = overridden;
+        self.profile_overrides = profile_overrides;
+    }
+
+    /// 切换档案后对新档案重新应用作用于当前档案的启动参数
+    fn reapply_profile_overrides(&mut self) {
+        let overrides = std::mem::take(&mut self.profile_overrides);
+        self.apply_override_list(&overrides);
+    }
+
+    /// 手动修改后作用于当前档案的启动参数不再生效，各档案恢复配置文件中的值
+    fn restore_profile_overrides(&mut self) {
+        let overrides = std::mem::take(&mut self.profile_overrides);
+        let restored: Vec<(String, Value)> = self
+            .overridden
+            .iter()
+            .filter(|(key, _)| {
+                key.starts_with("/profiles/") && overrides.iter().any(|item| key.ends_with(&item.pointer))
+            })
+            .map(|(key, original)| (key.clone(), original.clone()))
+            .collect();
+        for (key, _) in &restored {
+            self.overridden.remove(key);
+        }
+        self.edit_value(restored);
+    }
+
+    /// 新建的档案复制了默认档案当前的值，其中被启动参数覆盖的字段改回配置文件中的原值
+    fn restore_copied_defaults(&mut self) {
+        let target = self.active_profile_pointer();
+        let originals = self
+            .overridden
+            .iter()
+            .filter_map(|(key, original)| {
+                let rest = key.strip_prefix("/profiles/default/")?;
+                Some((format!("{}/{}", target, rest), original.clone()))
+            })
+            .collect();
+        self.edit_value(originals);
+    }
+
+    /// 按 JSON Pointer 修改字段，修改后无法解析时保持不变
+    fn edit_value(&mut self, values: Vec<(String, Value)>) {
+        if values.is_empty() {
+            return;
+        }
+        let Ok(mut value) = serde_json::to_value(&*self) else {
+            return;
+        };
+        for (pointer, new_value) in values {
+            if let Some(slot) = value.pointer_mut(&pointer) {
+                *slot = new_value;
+            }
+        }
+        if let Ok(mut config) = serde_json::from_value::<Self>(value) {
+            config.overridden = std::mem::take(&mut self.overridden);
+            config.profile_overrides = std::mem::take(&mut self.profile_overrides);
+            config.read_only = self.read_only;
+            *self = config;
+        }
+    }
+
+    /// 要写入文件的配置，被启动参数覆盖的字段还原为原值
+    pub fn persisted_value(&self) -> Result<Value, serde_json::Error> {
+        let mut value = serde_json::to_value(self)?;
+        for (pointer, original) in &self.overridden {
+            if let Some(slot) = value.pointer_mut(pointer) {
+                *slot = original.clone();
+            }
+        }
+        Ok(value)
+    }
+
+    /// 字段在运行中被修改后按新值保存，不再写回启动参数覆盖前的原值
+    ///
+    /// 覆盖的是上级字段（例如 `--discord={...}`）时，只把原值中对应的部分替换为新值。
+    fn clear_override(&mut self, pointer: &str) {
+        if self.overridden.is_empty() {
+            return;
+        }
+        let current = serde_json::to_value(&*self)
+            .ok()
+            .and_then(|value| value.pointer(pointer).cloned());
+        let nested = format!("{}/", pointer);
+        self.overridden.retain(|key, _| key != pointer && !key.starts_with(&nested));
+        for (key, original) in self.overridden.iter_mut() {
+            let Some(rest) = pointer.strip_prefix(key.as_str()).filter(|rest| rest.starts_with('/')) else {
+                continue;
+            };
+            if let (Some(slot), Some(current)) = (original.pointer_mut(rest), &current) {
+                *slot = current.clone();
+            }
+        }
+    }
+
+    /// 启动参数中的字段路径，`/auto_accept` 指向当前档案的自动接受设置
+    fn resolve_pointer(&self, pointer: &str) -> String {
+        match pointer {
+            "/auto_accept" => format!("{}/auto_accept", self.active_profile_pointer()),
+            _ => pointer.to_string(),
+        }
+    }
+
+    /// 当前配置档案的 JSON Pointer 路径
+    fn active_profile_pointer(&self) -> String {
+        let profiles = &self.profiles;
+        match profiles.active.as_ref().filter(|puuid| profiles.accounts.contains_key(*puuid)) {
+            Some(puuid) => format!("/profiles/accounts/{}", escape_pointer(puuid)),
+            None => "/profiles/default".to_string(),
+        }
+    }
+
+    /// 备份当前配置文件到 `config.json.bak`
+    fn backup(path: &Path) {
+        let backup_path = path.with_extension("json.bak");
+        match fs::copy(path, &backup_path) {
+            Ok(_) => info!("已备份原配置文件到: {:?}", backup_path),
+            Err(e) => warn!("备份配置文件失败: {}", e),
+        }
     }
 
     /// 保存配置到文件
+    ///
+    /// 先写入临时文件再重命名，避免写入中途退出导致配置文件损坏。
+    /// 配置来自更新版本时拒绝写入。
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let path = Self::config_path()?;
-        let content = serde_json::to_string_pretty(self)?;
-        fs::write(&path, content)?;
-        println!("配置已保存到: {:?}", path);
+        self.check_writable()?;
+        Self::write_file(&self.to_file_content()?)
+    }
+
+    /// 配置来自更新版本的 micept 时返回错误
+    pub fn check_writable(&self) -> Result<(), String> {
+        if self.read_only {
+            return Err(format!(
+                "配置文件版本{}高于当前支持的版本{}，为避免覆盖新版本的设置，修改不会保存，请升级 micept",
+                self.version, CONFIG_VERSION
+            ));
+        }
+        Ok(())
+    }
+
+    /// 配置文件内容
+    pub fn to_file_content(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.persisted_value()?)
+    }
+
+    /// 将内容写入配置文件
+    pub fn write_file(content: &str) -> Result<(), Box<dyn std::error::Error>> {
+        Self::write_to(&Self::config_path()?, content)
+    }
+
+    fn write_to(path: &Path, content: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(&temp_path, path)?;
+        debug!("配置已保存到: {:?}", path);
         Ok(())
     }
+}
+
+/// 对配置的一次修改，由后台写入任务合并保存
+#[derive(Debug, Clone)]
+pub enum ConfigUpdate {
+    WindowPosition { x: i32, y: i32 },
+    MouseThrough(bool),
+    AutoAccept(bool),
+    AutoHide(bool),
+    WindowVisible(bool),
+    DiscordEnabled(bool),
+    Autostart(bool),
+    LogLevel(String),
+    /// 切换配置档案，`puuid` 为 None 时使用默认档案
+    ActiveProfile { puuid: Option<String>, name: String },
+    /// 替换当前档案的选用和禁用英雄
+    ChampionLists(ChampionLists),
+    /// 替换当前档案的符文页
+    RunePages(Vec<RunePage>),
+    /// 整体替换配置，例如导入配置包
+    Replace(Box<AppConfig>),
+    /// 与启动参数一样覆盖配置，只在本次运行中生效，例如第二次启动转发的开关参数
+    LaunchOverrides(Vec<ConfigOverride>),
+}
+
+impl ConfigUpdate {
+    /// 将修改应用到内存中的配置
+    ///
+    /// 被修改的字段即使在启动时被参数覆盖，保存时也使用新值。
+    /// 切换档案不算修改，`--auto-accept` 等启动参数在新档案上继续生效。
+    pub fn apply(&self, config: &mut AppConfig) {
+        match *self {
+            ConfigUpdate::WindowPosition { x, y } => config.window_position = WindowPosition { x, y },
+            ConfigUpdate::MouseThrough(enabled) => config.mouse_through = enabled,
+            ConfigUpdate::AutoAccept(enabled) => {
+                config.restore_profile_overrides();
+                config.profiles.active_profile_mut().auto_accept = enabled;
+            }
+            ConfigUpdate::AutoHide(enabled) => config.auto_hide = enabled,
+            ConfigUpdate::WindowVisible(visible) => config.window_visible = visible,
+            ConfigUpdate::DiscordEnabled(enabled) => config.discord.enabled = enabled,
+            ConfigUpdate::Autostart(enabled) => config.autostart = enabled,
+            ConfigUpdate::LogLevel(ref level) => config.log_level = level.clone(),
+            ConfigUpdate::ActiveProfile { ref puuid, ref name } => {
+                let created = puuid
+                    .as_ref()
+                    .is_some_and(|puuid| !config.profiles.accounts.contains_key(puuid));
+                config.profiles.activate(puuid.as_deref(), name);
+                if created {
+                    config.restore_copied_defaults();
+                    config.clear_override(&config.active_profile_pointer());
+                }
+                config.reapply_profile_overrides();
+            }
+            ConfigUpdate::ChampionLists(ref lists) => config.profiles.active_profile_mut().champion_lists = lists.clone(),
+            ConfigUpdate::RunePages(ref pages) => config.profiles.active_profile_mut().rune_pages = pages.clone(),
+            ConfigUpdate::Replace(ref replacement) => *config = (**replacement).clone(),
+            ConfigUpdate::LaunchOverrides(ref overrides) => config.apply_override_list(overrides),
+        }
 
-    /// 更新窗口位置
-    pub fn update_window_position(&mut self, x: i32, y: i32) {
-        self.window_position.x = x;
-        self.window_position.y = y;
-        if let Err(e) = self.save() {
-            println!("保存窗口位置失败: {}", e);
+        for pointer in self.fields(config) {
+            config.clear_override(&pointer);
         }
     }
 
-    /// 更新鼠标穿透状态
-    pub fn update_mouse_through(&mut self, enabled: bool) {
-        self.mouse_through = enabled;
-        if let Err(e) = self.save() {
-            println!("保存鼠标穿透状态失败: {}", e);
+    /// 修改涉及的字段，JSON Pointer 形式
+    fn fields(&self, config: &AppConfig) -> Vec<String> {
+        let field = |name: &str| vec![name.to_string()];
+        match self {
+            ConfigUpdate::WindowPosition { .. } => field("/window_position"),
+            ConfigUpdate::MouseThrough(_) => field("/mouse_through"),
+            ConfigUpdate::AutoAccept(_) => vec![format!("{}/auto_accept", config.active_profile_pointer())],
+            ConfigUpdate::AutoHide(_) => field("/auto_hide"),
+            ConfigUpdate::WindowVisible(_) => field("/window_visible"),
+            ConfigUpdate::DiscordEnabled(_) => field("/discord/enabled"),
+            ConfigUpdate::Autostart(_) => field("/autostart"),
+            ConfigUpdate::LogLevel(_) => field("/log_level"),
+            ConfigUpdate::ActiveProfile { .. } => field("/profiles/active"),
+            ConfigUpdate::ChampionLists(_) => vec![format!("{}/champion_lists", config.active_profile_pointer())],
+            ConfigUpdate::RunePages(_) => vec![format!("{}/rune_pages", config.active_profile_pointer())],
+            // 替换后的配置不再带有启动参数覆盖
+            ConfigUpdate::Replace(_) => Vec::new(),
+            // 覆盖本身不是修改，保存时仍使用原值
+            ConfigUpdate::LaunchOverrides(_) => Vec::new(),
         }
     }
+}
+
+/// 将 `incoming` 合并到 `root` 中 `pointer` 指向的字段，合并后无法解析时保留原值并记录到 `rejected`
+///
+/// 对象逐个字段合并，因此嵌套结构中的单个无效字段不会导致整个结构被丢弃。
+fn merge_field(root: &mut Value, pointer: &str, incoming: Value, rejected: &mut Vec<String>) {
+    let is_valid = |root: &Value| serde_json::from_value::<AppConfig>(root.clone()).is_ok();
+    let previous = root.pointer(pointer).cloned();
+
+    set_pointer(root, pointer, Some(incoming.clone()));
+    if is_valid(root) {
+        return;
+    }
 
-    /// 更新自动接受状态
-    pub fn update_auto_accept(&mut self, enabled: bool) {
-        self.auto_accept = enabled;
-        if let Err(e) = self.save() {
-            println!("保存自动接受状态失败: {}", e);
+    if let Value::Object(fields) = incoming {
+        let base = match previous.clone() {
+            Some(Value::Object(object)) => object,
+            _ => Map::new(),
+        };
+        set_pointer(root, pointer, Some(Value::Object(base)));
+        if is_valid(root) {
+            for (key, value) in fields {
+                merge_field(root, &format!("{}/{}", pointer, escape_pointer(&key)), value, rejected);
+            }
+            return;
+        }
+    }
+
+    set_pointer(root, pointer, previous);
+    rejected.push(
+        pointer
+            .trim_start_matches('/')
+            .split('/')
+            .map(unescape_pointer)
+            .collect::<Vec<_>>()
+            .join("."),
+    );
+}
+
+/// 设置或删除（`value` 为 None 时）JSON Pointer 指向的字段，上级必须是已存在的对象
+fn set_pointer(root: &mut Value, pointer: &str, value: Option<Value>) {
+    let Some((parent, key)) = pointer.rsplit_once('/') else {
+        return;
+    };
+    if let Some(Value::Object(parent)) = root.pointer_mut(parent) {
+        let key = unescape_pointer(key);
+        match value {
+            Some(value) => {
+                parent.insert(key, value);
+            }
+            None => {
+                parent.remove(&key);
+            }
         }
     }
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape_pointer(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+/// 版本 0 是没有 `version` 字段的早期配置，字段与版本 1 相同，
+/// 后来加入的通知、Webhook 等配置由 `#[serde(default)]` 补齐
+fn migrate_v0_to_v1(_fields: &mut Map<String, Value>) {}
 
-    /// 更新窗口可见性
-    pub fn update_window_visible(&mut self, visible: bool) {
-        self.window_visible = visible;
-        if let Err(e) = self.save() {
-            println!("保存窗口可见性失败: {}", e);
+/// 版本 2 起自动接受按账号保存，原有设置迁移到默认档案
+fn migrate_v1_to_v2(fields: &mut Map<String, Value>) {
+    let auto_accept = fields.get("auto_accept").cloned().unwrap_or(Value::Bool(true));
+    let profiles = fields
+        .entry("profiles")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(profiles) = profiles {
+        let default = profiles
+            .entry("default")
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(default) = default {
+            default.entry("auto_accept").or_insert(auto_accept);
         }
     }
+}
+
+/// 版本 3 起自动接受只保存在配置档案中，顶层字段写入当前档案后移除
+fn migrate_v2_to_v3(fields: &mut Map<String, Value>) {
+    let Some(auto_accept) = fields.remove("auto_accept") else {
+        return;
+    };
+    let Value::Object(profiles) = fields
+        .entry("profiles")
+        .or_insert_with(|| Value::Object(Map::new()))
+    else {
+        return;
+    };
+    let active = profiles
+        .get("active")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .filter(|puuid| profiles.get("accounts").and_then(|accounts| accounts.get(puuid)).is_some());
+    let profile = match active {
+        Some(puuid) => profiles.get_mut("accounts").and_then(|accounts| accounts.get_mut(&puuid)),
+        None => Some(profiles.entry("default").or_insert_with(|| Value::Object(Map::new()))),
+    };
+    if let Some(Value::Object(profile)) = profile {
+        profile.insert("auto_accept".to_string(), auto_accept);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+    use crate::utils::launch::LaunchOptions;
+
+    /// 按命令行参数覆盖配置
+    fn overridden(config: &mut AppConfig, args: &[&str]) {
+        let args = args.iter().map(|arg| arg.to_string());
+        let options = LaunchOptions::parse(args, Vec::new()).unwrap();
+        config.apply_override_list(&options.overrides);
+    }
+
+    fn temp_config(name: &str, content: &str) -> PathBuf {
+        let path = temp_dir(&format!("config-{}", name)).join("config.json");
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn unversioned_config_is_migrated_to_current_version() {
+        let (config, migrated_from, rejected) = AppConfig::parse(r#"{"auto_accept":false,"auto_hide":true}"#).unwrap();
+        assert_eq!(migrated_from, Some(0));
+        assert!(rejected.is_empty());
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert!(config.auto_hide);
+        // 版本 2 把自动接受迁移到默认档案
+        assert!(!config.profiles.default.auto_accept);
+
+        let (config, migrated_from, _) = AppConfig::parse(r#"{"version":1,"profiles":{"default":{"name":"我"}}}"#).unwrap();
+        assert_eq!(migrated_from, Some(1));
+        assert_eq!(config.profiles.default.name, "我");
+        assert!(config.profiles.default.auto_accept);
+
+        // 版本 3 把顶层的自动接受写入当前档案
+        let content = r#"{"version":2,"auto_accept":false,"profiles":{"active":"p1","accounts":{"p1":{"name":"A","auto_accept":true}}}}"#;
+        let (config, migrated_from, _) = AppConfig::parse(content).unwrap();
+        assert_eq!(migrated_from, Some(2));
+        assert!(!config.auto_accept());
+        assert!(config.profiles.default.auto_accept);
+        assert!(config.persisted_value().unwrap().get("auto_accept").is_none());
+
+        let (_, migrated_from, _) = AppConfig::parse(r#"{"version":3}"#).unwrap();
+        assert_eq!(migrated_from, None);
+    }
 
-    /// 更新自动隐藏状态
-    pub fn update_auto_hide(&mut self, enabled: bool) {
-        self.auto_hide = enabled;
-        if let Err(e) = self.save() {
-            println!("保存自动隐藏状态失败: {}", e);
+    #[test]
+    fn newer_version_is_loaded_read_only() {
+        let content = r#"{"version":4,"auto_hide":true,"future_setting":{"level":3}}"#;
+        let (config, migrated_from, _) = AppConfig::parse(content).unwrap();
+        assert!(config.read_only);
+        assert_eq!(migrated_from, None);
+        assert_eq!(config.version, 4);
+        assert!(config.auto_hide);
+        assert_eq!(config.extra["future_setting"]["level"], 3);
+        assert!(config.check_writable().is_err());
+
+        // 加载时不写回，修改后也不会覆盖
+        let path = temp_config("newer", content);
+        let mut config = AppConfig::load_from(&path);
+        assert!(config.read_only);
+        ConfigUpdate::AutoHide(false).apply(&mut config);
+        assert!(config.read_only);
+        assert!(config.save().is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+        assert!(!path.with_extension("json.bak").exists());
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn invalid_version_is_refused() {
+        for content in [
+            r#"{"version":4294967298}"#,
+            r#"{"version":-1}"#,
+            r#"{"version":"2"}"#,
+            "[]",
+        ] {
+            assert!(AppConfig::parse(content).is_err(), "{}", content);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn invalid_fields_fall_back_individually() {
+        let content = r#"{
+            "version": 2,
+            "auto_hide": "yes",
+            "mouse_through": false,
+            "notifications": { "ready_check": false, "quiet_hours": { "enabled": true, "start": 5 } },
+            "profiles": { "accounts": { "p1": { "name": "A", "auto_accept": "no" } } }
+        }"#;
+        let (config, _, rejected) = AppConfig::parse(content).unwrap();
+
+        assert_eq!(
+            rejected,
+            vec!["auto_hide", "notifications.quiet_hours.start", "profiles.accounts.p1.auto_accept"]
+        );
+        assert!(!config.auto_hide);
+        assert!(!config.mouse_through);
+        assert!(!config.notifications.ready_check);
+        assert!(config.notifications.quiet_hours.enabled);
+        assert_eq!(config.notifications.quiet_hours.start, "23:00");
+        assert_eq!(config.profiles.accounts["p1"].name, "A");
+    }
+
+    #[test]
+    fn unknown_fields_survive_save() {
+        let content = r#"{"version":2,"future_setting":{"level":3},"auto_hide":"bad"}"#;
+        let (config, _, rejected) = AppConfig::parse(content).unwrap();
+        assert_eq!(rejected, vec!["auto_hide"]);
+
+        let saved: Value = serde_json::from_str(&config.to_file_content().unwrap()).unwrap();
+        assert_eq!(saved["future_setting"]["level"], 3);
+    }
+
+    #[test]
+    fn broken_or_migrated_file_is_backed_up_and_rewritten() {
+        let path = temp_config("migrate", r#"{"auto_accept":false,"auto_hide":"bad"}"#);
+        let config = AppConfig::load_from(&path);
+        assert!(!config.auto_accept());
+
+        let backup = fs::read_to_string(path.with_extension("json.bak")).unwrap();
+        assert_eq!(backup, r#"{"auto_accept":false,"auto_hide":"bad"}"#);
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["version"], CONFIG_VERSION);
+        assert_eq!(saved["auto_hide"], false);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+
+        let path = temp_config("broken", "{ not json");
+        let config = AppConfig::load_from(&path);
+        assert!(config.auto_accept());
+        assert_eq!(fs::read_to_string(path.with_extension("json.bak")).unwrap(), "{ not json");
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn overridden_fields_are_saved_with_file_values() {
+        let mut config = AppConfig::default();
+        overridden(&mut config, &["--no-auto-accept", "--api.port=18000", "--unknown=1", "--api.enabled=maybe"]);
+
+        assert!(!config.auto_accept());
+        assert_eq!(config.api.port, 18000);
+        assert!(!config.api.enabled);
+
+        let persisted = config.persisted_value().unwrap();
+        assert_eq!(persisted["profiles"]["default"]["auto_accept"], true);
+        assert_eq!(persisted["api"]["port"], 17321);
+        assert_eq!(
+            config.overridden.keys().collect::<Vec<_>>(),
+            vec!["/api/port", "/profiles/default/auto_accept"]
+        );
+    }
+
+    #[test]
+    fn updating_overridden_field_saves_new_value() {
+        let mut config = AppConfig::default();
+        overridden(&mut config, &["--no-auto-accept", "--no-mouse-through"]);
+
+        ConfigUpdate::AutoAccept(false).apply(&mut config);
+        let persisted = config.persisted_value().unwrap();
+        assert_eq!(persisted["profiles"]["default"]["auto_accept"], false);
+        // 未修改的字段仍然写回原值
+        assert_eq!(persisted["mouse_through"], true);
+
+        let reloaded: AppConfig = serde_json::from_value(persisted).unwrap();
+        assert!(!reloaded.auto_accept());
+    }
+
+    #[test]
+    fn updating_part_of_overridden_section_keeps_other_file_values() {
+        let mut config = AppConfig::default();
+        overridden(&mut config, &[r#"--discord={"enabled":true,"client_id":"123"}"#]);
+        assert_eq!(config.discord.client_id, "123");
+
+        ConfigUpdate::DiscordEnabled(false).apply(&mut config);
+        let persisted = config.persisted_value().unwrap();
+        assert_eq!(persisted["discord"]["enabled"], false);
+        assert_eq!(persisted["discord"]["client_id"], "");
+    }
+
+    #[test]
+    fn champion_lists_and_rune_pages_belong_to_active_profile() {
+        let mut config = AppConfig::default();
+        ConfigUpdate::ActiveProfile {
+            puuid: Some("puuid-a".to_string()),
+            name: "A".to_string(),
+        }
+        .apply(&mut config);
+        ConfigUpdate::ChampionLists(ChampionLists { pick: vec![103], ban: vec![157] }).apply(&mut config);
+        ConfigUpdate::RunePages(vec![RunePage { name: "中单".to_string(), ..Default::default() }]).apply(&mut config);
+
+        let account = &config.profiles.accounts["puuid-a"];
+        assert_eq!(account.champion_lists.pick, vec![103]);
+        assert_eq!(account.rune_pages[0].name, "中单");
+        assert!(config.profiles.default.champion_lists.pick.is_empty());
+        assert!(config.profiles.default.rune_pages.is_empty());
+    }
+
+    #[test]
+    fn launch_override_survives_profile_switch() {
+        let switch = |config: &mut AppConfig, puuid: Option<&str>| {
+            ConfigUpdate::ActiveProfile {
+                puuid: puuid.map(str::to_string),
+                name: "A".to_string(),
+            }
+            .apply(config)
+        };
+        let mut config = AppConfig::default();
+        overridden(&mut config, &["--no-auto-accept"]);
+
+        switch(&mut config, Some("puuid-a"));
+        assert!(!config.auto_accept());
+        switch(&mut config, None);
+        assert!(!config.auto_accept());
+        switch(&mut config, Some("puuid-a"));
+        assert!(!config.auto_accept());
+
+        // 保存时各档案仍是配置文件中的值
+        let persisted = config.persisted_value().unwrap();
+        assert_eq!(persisted["profiles"]["active"], "puuid-a");
+        assert_eq!(persisted["profiles"]["default"]["auto_accept"], true);
+        assert_eq!(persisted["profiles"]["accounts"]["puuid-a"]["auto_accept"], true);
+
+        // 手动修改后启动参数不再生效
+        ConfigUpdate::AutoAccept(true).apply(&mut config);
+        switch(&mut config, None);
+        assert!(config.auto_accept());
+        assert!(config.overridden.is_empty());
+    }
+}
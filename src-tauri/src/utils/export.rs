@@ -0,0 +1,154 @@
+use crate::lol::{self, MatchRow};
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+/// 默认导出的对局数量
+pub const DEFAULT_MATCH_LIMIT: usize = 100;
+
+/// 导出文件格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// 从字符串解析格式，未指定时根据文件扩展名推断
+    pub fn parse(format: Option<&str>, path: &Path) -> Result<Self, String> {
+        let format = match format {
+            Some(format) => format.to_lowercase(),
+            None => path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("csv")
+                .to_lowercase(),
+        };
+        match format.as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            other => Err(format!("不支持的导出格式: {}", other)),
+        }
+    }
+}
+
+const CSV_HEADER: &str = "date,queue,champion,kills,deaths,assists,kda,cs,gold,damage,win";
+
+/// 转义CSV字段
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 将导出行格式化为CSV文本
+fn rows_to_csv(rows: &[MatchRow]) -> String {
+    let mut content = String::from(CSV_HEADER);
+    content.push('\n');
+    for row in rows {
+        content.push_str(&format!(
+            "{},{},{},{},{},{},{:.2},{},{},{},{}\n",
+            csv_field(&row.date),
+            csv_field(&row.queue),
+            csv_field(&row.champion),
+            row.kills,
+            row.deaths,
+            row.assists,
+            row.kda,
+            row.cs,
+            row.gold,
+            row.damage,
+            row.win
+        ));
+    }
+    content
+}
+
+/// 将导出行写入文件
+pub fn write_rows(rows: &[MatchRow], path: &Path, format: ExportFormat) -> Result<(), String> {
+    let content = match format {
+        ExportFormat::Csv => rows_to_csv(rows),
+        ExportFormat::Json => serde_json::to_string_pretty(rows)
+            .map_err(|e| format!("序列化对局记录失败: {}", e))?,
+    };
+    fs::write(path, content).map_err(|e| format!("写入文件失败: {}", e))
+}
+
+/// 获取对局记录并导出到指定路径，返回导出的对局数量
+pub async fn export_match_history(
+    port: &str,
+    token: &str,
+    path: &Path,
+    format: ExportFormat,
+    limit: usize,
+) -> Result<usize, String> {
+    let rows = lol::fetch_match_rows(port, token, limit).await?;
+    write_rows(&rows, path, format)?;
+    info!("已导出{}局对局记录到: {:?}", rows.len(), path);
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(queue: &str, champion: &str) -> MatchRow {
+        MatchRow {
+            date: "2024-01-02 03:04".to_string(),
+            queue: queue.to_string(),
+            champion: champion.to_string(),
+            kills: 1,
+            deaths: 2,
+            assists: 3,
+            kda: 2.0,
+            cs: 150,
+            gold: 9000,
+            damage: 12345,
+            win: true,
+        }
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_and_escaped() {
+        assert_eq!(csv_field("阿狸"), "阿狸");
+        assert_eq!(csv_field("单排,双排"), "\"单排,双排\"");
+        assert_eq!(csv_field("Kai\"Sa"), "\"Kai\"\"Sa\"");
+        assert_eq!(csv_field("a\nb"), "\"a\nb\"");
+        assert_eq!(csv_field("a\rb"), "\"a\rb\"");
+    }
+
+    #[test]
+    fn csv_has_header_and_one_line_per_row() {
+        let csv = rows_to_csv(&[row("单排/双排", "Kai'Sa"), row("极地,大乱斗", "Nunu \"Willump\"")]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "2024-01-02 03:04,单排/双排,Kai'Sa,1,2,3,2.00,150,9000,12345,true");
+        assert_eq!(
+            lines[2],
+            "2024-01-02 03:04,\"极地,大乱斗\",\"Nunu \"\"Willump\"\"\",1,2,3,2.00,150,9000,12345,true"
+        );
+        assert_eq!(lines.len(), 3);
+        assert_eq!(rows_to_csv(&[]), format!("{}\n", CSV_HEADER));
+    }
+
+    #[test]
+    fn format_follows_argument_then_extension() {
+        assert_eq!(ExportFormat::parse(None, Path::new("matches.json")).unwrap(), ExportFormat::Json);
+        assert_eq!(ExportFormat::parse(None, Path::new("matches.CSV")).unwrap(), ExportFormat::Csv);
+        assert_eq!(ExportFormat::parse(None, Path::new("matches")).unwrap(), ExportFormat::Csv);
+        assert_eq!(ExportFormat::parse(Some("JSON"), Path::new("matches.csv")).unwrap(), ExportFormat::Json);
+        assert!(ExportFormat::parse(None, Path::new("matches.xlsx")).is_err());
+    }
+
+    #[test]
+    fn json_export_round_trips() {
+        let path = std::env::temp_dir().join(format!("micept-export-{}.json", std::process::id()));
+        write_rows(&[row("单排/双排", "阿狸")], &path, ExportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(value[0]["champion"], "阿狸");
+        assert_eq!(value[0]["win"], true);
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,253 @@
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing::info;
+
+/// 环境变量前缀，嵌套字段用双下划线分隔，例如 `MICEPT_API__PORT`
+const ENV_PREFIX: &str = "MICEPT_";
+
+/// 可执行文件旁存在该文件时启用便携模式
+const PORTABLE_MARKER: &str = "portable";
+
+pub const LAUNCH_USAGE: &str = "用法: micept [--config <路径>] [--portable] [--no-window] [--minimized] [--headless] [--<配置项>=<值>] [--no-<配置项>]\n\
+配置项使用下划线或连字符，嵌套字段用点分隔，例如 --auto-accept=false --api.port=18000\n\
+Windows 下输出显示在启动本程序的命令行窗口；未以管理员身份运行时会在新进程中提权，无界面模式的输出请在管理员命令行中运行查看";
+
+static LAUNCH_OPTIONS: OnceLock<LaunchOptions> = OnceLock::new();
+
+/// 本次运行的启动参数，只在本次运行中生效，不会写入配置文件
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    /// 指定的配置文件路径
+    pub config_path: Option<PathBuf>,
+    /// 便携模式，配置保存在可执行文件旁
+    pub portable: bool,
+    /// 最小化启动（开机自启时使用），启动时不显示窗口，但不修改保存的 `window_visible`
+    pub minimized: bool,
+    /// 无界面模式，不创建窗口和托盘，只运行后台任务和集成
+    pub headless: bool,
+    /// 覆盖的配置项，按出现顺序应用，后出现的优先
+    pub overrides: Vec<ConfigOverride>,
+}
+
+/// 一项配置覆盖
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigOverride {
+    /// 来源，用于提示，例如 `--auto-accept` 或 `MICEPT_AUTO_ACCEPT`
+    pub source: String,
+    /// JSON Pointer 形式的字段路径，例如 `/api/port`
+    pub pointer: String,
+    pub value: String,
+}
+
+impl ConfigOverride {
+    fn new(source: &str, key: &str, separator: &str, value: &str) -> Self {
+        let pointer = key
+            .split(separator)
+            .map(|segment| segment.to_lowercase().replace('-', "_"))
+            .fold(String::new(), |pointer, segment| format!("{}/{}", pointer, segment));
+        Self {
+            source: source.to_string(),
+            pointer,
+            value: value.to_string(),
+        }
+    }
+
+    /// 候选值：先按 JSON 解析（布尔值、数字、数组），再作为字符串
+    pub fn candidates(&self) -> Vec<Value> {
+        let mut candidates = Vec::new();
+        if let Ok(value) = serde_json::from_str::<Value>(&self.value) {
+            candidates.push(value);
+        }
+        candidates.push(Value::String(self.value.clone()));
+        candidates
+    }
+}
+
+impl LaunchOptions {
+    /// 解析环境变量和命令行参数，命令行参数优先
+    pub fn parse<A, E>(args: A, env: E) -> Result<Self, String>
+    where
+        A: IntoIterator<Item = String>,
+        E: IntoIterator<Item = (String, String)>,
+    {
+        let mut options = Self::default();
+
+        for (name, value) in env {
+            let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            match key {
+                "CONFIG" => options.config_path = Some(PathBuf::from(value)),
+                "PORTABLE" => options.portable = is_truthy(&value),
+                _ => options.overrides.push(ConfigOverride::new(&name, key, "__", &value)),
+            }
+        }
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let Some(option) = arg.strip_prefix("--") else {
+                return Err(format!("未知参数: {}\n{}", arg, LAUNCH_USAGE));
+            };
+            let (key, value) = match option.split_once('=') {
+                Some((key, value)) => (key, Some(value.to_string())),
+                None => (option, None),
+            };
+
+            match key {
+                "config" => {
+                    let path = value.or_else(|| args.next()).ok_or(LAUNCH_USAGE)?;
+                    options.config_path = Some(PathBuf::from(path));
+                }
+                "portable" => options.portable = value.as_deref().is_none_or(is_truthy),
+                "minimized" => options.minimized = true,
+                "headless" => options.headless = true,
+                "no-window" => options.overrides.push(ConfigOverride::new(&arg, "window_visible", ".", "false")),
+                _ => match (key.strip_prefix("no-"), value) {
+                    (Some(key), None) => options.overrides.push(ConfigOverride::new(&arg, key, ".", "false")),
+                    (_, Some(value)) => options.overrides.push(ConfigOverride::new(&arg, key, ".", &value)),
+                    (None, None) => options.overrides.push(ConfigOverride::new(&arg, key, ".", "true")),
+                },
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// 便携模式下的配置目录（可执行文件所在目录），未启用时为 None
+    ///
+    /// 通过 `--portable`、`MICEPT_PORTABLE=1` 或在可执行文件旁放置 `portable` 文件启用。
+    pub fn portable_dir(&self) -> Option<PathBuf> {
+        let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+        (self.portable || exe_dir.join(PORTABLE_MARKER).exists()).then_some(exe_dir)
+    }
+}
+
+/// 将子命令参数中的 `--config` 和 `--portable` 分离出来
+///
+/// 返回 (启动参数, 子命令参数)，子命令据此读写同一个配置文件。
+pub fn split_location_args(args: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut launch = Vec::new();
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.split_once('=').map_or(arg.as_str(), |(key, _)| key) {
+            "--config" => {
+                launch.push(arg.clone());
+                if !arg.contains('=') {
+                    launch.extend(args.next().cloned());
+                }
+            }
+            "--portable" => launch.push(arg.clone()),
+            _ => rest.push(arg.clone()),
+        }
+    }
+    (launch, rest)
+}
+
+/// 第一个不属于启动参数的位置参数的下标，即子命令名称所在位置
+pub fn first_positional(args: &[String]) -> Option<usize> {
+    let mut index = 0;
+    while index < args.len() {
+        let arg = &args[index];
+        if !arg.starts_with("--") {
+            return Some(index);
+        }
+        // `--config <路径>` 的路径不是位置参数
+        index += if arg == "--config" { 2 } else { 1 };
+    }
+    None
+}
+
+fn is_truthy(value: &str) -> bool {
+    matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on")
+}
+
+/// 设置本次运行的启动参数，只能设置一次
+pub fn init(options: LaunchOptions) {
+    if LAUNCH_OPTIONS.set(options).is_err() {
+        info!("启动参数已设置，忽略重复设置");
+    }
+}
+
+/// 本次运行的启动参数，未设置时为空
+pub fn options() -> &'static LaunchOptions {
+    LAUNCH_OPTIONS.get_or_init(LaunchOptions::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str], env: &[(&str, &str)]) -> LaunchOptions {
+        let args = args.iter().map(|arg| arg.to_string());
+        let env = env.iter().map(|(name, value)| (name.to_string(), value.to_string()));
+        LaunchOptions::parse(args, env).unwrap()
+    }
+
+    fn overrides(options: &LaunchOptions) -> Vec<(&str, &str)> {
+        options
+            .overrides
+            .iter()
+            .map(|item| (item.pointer.as_str(), item.value.as_str()))
+            .collect()
+    }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_become_overrides() {
+        let options = parse(&["--no-auto-accept", "--auto-hide", "--api.port=18000", "--mouse_through=false"], &[]);
+        assert_eq!(
+            overrides(&options),
+            vec![
+                ("/auto_accept", "false"),
+                ("/auto_hide", "true"),
+                ("/api/port", "18000"),
+                ("/mouse_through", "false"),
+            ]
+        );
+    }
+
+    #[test]
+    fn env_nesting_uses_double_underscore_and_args_win() {
+        let options = parse(
+            &["--api.port=19000", "--config", "custom.json"],
+            &[("MICEPT_API__PORT", "18000"), ("MICEPT_PORTABLE", "1"), ("OTHER", "x")],
+        );
+        // 命令行参数排在后面，应用时覆盖环境变量
+        assert_eq!(overrides(&options), vec![("/api/port", "18000"), ("/api/port", "19000")]);
+        assert!(options.portable);
+        assert_eq!(options.config_path, Some(PathBuf::from("custom.json")));
+    }
+
+    #[test]
+    fn launch_only_flags_are_not_overrides() {
+        let options = parse(&["--minimized", "--headless", "--portable=false", "--no-window"], &[]);
+        assert!(options.minimized && options.headless && !options.portable);
+        assert_eq!(overrides(&options), vec![("/window_visible", "false")]);
+    }
+
+    #[test]
+    fn positional_argument_is_rejected() {
+        let args = ["auto-accept".to_string()];
+        assert!(LaunchOptions::parse(args, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn location_args_are_split_from_subcommand_args() {
+        let args = strings(&["--output", "a.json", "--config", "b.json", "--portable", "--config=c.json"]);
+        let (launch, rest) = split_location_args(&args);
+        assert_eq!(launch, strings(&["--config", "b.json", "--portable", "--config=c.json"]));
+        assert_eq!(rest, strings(&["--output", "a.json"]));
+    }
+
+    #[test]
+    fn subcommand_position_skips_config_path() {
+        assert_eq!(first_positional(&strings(&["--config", "status", "status"])), Some(2));
+        assert_eq!(first_positional(&strings(&["--portable", "export-config"])), Some(1));
+        assert_eq!(first_positional(&strings(&["--no-window"])), None);
+    }
+}
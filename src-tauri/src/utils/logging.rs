@@ -0,0 +1,74 @@
+use super::redact::RedactingMakeWriter;
+use super::AppConfig;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, Registry};
+
+/// 本程序日志的 target，第三方库只记录警告和错误
+const LOG_TARGET: &str = "micept_lib";
+
+/// 日志文件名前缀，按天滚动，例如 `micept.2024-01-01.log`
+const LOG_FILE_PREFIX: &str = "micept";
+
+/// 保留的日志文件数量
+const MAX_LOG_FILES: usize = 7;
+
+static FILTER_HANDLE: OnceLock<reload::Handle<Targets, Registry>> = OnceLock::new();
+
+/// 日志目录，位于配置目录下的 `logs`
+pub fn log_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    Ok(AppConfig::config_path()?.with_file_name("logs"))
+}
+
+/// 初始化日志，写入按天滚动的日志文件，调试构建同时输出到控制台
+///
+/// 所有输出都经过 `redact` 隐藏令牌、puuid 和召唤师名称。
+///
+/// 在加载配置之前调用，级别先使用 info，加载配置后由 `set_level` 调整。
+pub fn init() -> Result<(), String> {
+    let directory = log_dir().map_err(|e| format!("获取日志目录失败: {}", e))?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(&directory)
+        .map_err(|e| format!("创建日志文件失败: {}", e))?;
+
+    let (filter, handle) = reload::Layer::new(targets(LevelFilter::INFO));
+    let console = cfg!(debug_assertions).then(|| fmt::layer().with_writer(RedactingMakeWriter(std::io::stdout)));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(RedactingMakeWriter(appender)).with_ansi(false))
+        .with(console)
+        .try_init()
+        .map_err(|e| format!("初始化日志失败: {}", e))?;
+
+    let _ = FILTER_HANDLE.set(handle);
+    Ok(())
+}
+
+/// 解析日志级别，不区分大小写
+pub fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(level).map_err(|_| format!("无效的日志级别: {}，可选 error、warn、info、debug、trace", level))
+}
+
+/// 运行时调整日志级别
+pub fn set_level(level: &str) -> Result<(), String> {
+    let level = parse_level(level)?;
+    let handle = FILTER_HANDLE.get().ok_or("日志尚未初始化")?;
+    handle
+        .reload(targets(level))
+        .map_err(|e| format!("调整日志级别失败: {}", e))?;
+    tracing::info!(%level, "日志级别已调整");
+    Ok(())
+}
+
+fn targets(level: LevelFilter) -> Targets {
+    Targets::new()
+        .with_target(LOG_TARGET, level)
+        .with_default(LevelFilter::WARN)
+}
@@ -1,4 +1,10 @@
 /// 工具模块 - 配置管理等工具功能
 pub mod config;
+pub mod export;
+pub mod bundle;
+pub mod launch;
+pub mod logging;
+pub mod redact;
+pub mod autostart;
 
 pub use config::AppConfig;
\ No newline at end of file
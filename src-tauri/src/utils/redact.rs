@@ -0,0 +1,201 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::sync::RwLock;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// 替换敏感内容的文本
+pub const MASK: &str = "***";
+
+/// 短于该字符数的内容不登记，避免误伤普通文本
+const MIN_SECRET_CHARS: usize = 2;
+
+/// 短于该字符数的内容（通常是召唤师名称）只在前后不是字母或数字时替换，
+/// 例如名称"未知"不会隐藏"未知参数"中的文字
+const WORD_BOUNDARY_CHARS: usize = 8;
+
+/// 紧跟令牌的前缀，即使令牌尚未登记，其后的内容也会被隐藏
+const TOKEN_PREFIXES: &[&str] = &["--remoting-auth-token=", "\"token\":\"", "token="];
+
+/// 已登记的敏感内容：LCU 令牌、puuid 和召唤师名称
+static SECRETS: RwLock<BTreeSet<String>> = RwLock::new(BTreeSet::new());
+
+/// 登记需要在日志和诊断信息中隐藏的内容
+pub fn register(secret: &str) {
+    let secret = secret.trim();
+    if secret.chars().count() < MIN_SECRET_CHARS || secret == MASK {
+        return;
+    }
+    let mut secrets = SECRETS.write().unwrap();
+    if !secrets.contains(secret) {
+        secrets.insert(secret.to_string());
+    }
+}
+
+/// 隐藏文本中的令牌和已登记的敏感内容
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let mut result = Cow::Borrowed(text);
+
+    for prefix in TOKEN_PREFIXES {
+        if result.contains(prefix) {
+            result = Cow::Owned(mask_after_prefix(&result, prefix));
+        }
+    }
+
+    let secrets = SECRETS.read().unwrap();
+    // 先替换较长的内容，避免名称是令牌一部分时只替换了一半
+    let mut secrets: Vec<&String> = secrets.iter().filter(|secret| result.contains(secret.as_str())).collect();
+    secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+    for secret in secrets {
+        result = Cow::Owned(if secret.chars().count() < WORD_BOUNDARY_CHARS {
+            replace_word(&result, secret)
+        } else {
+            result.replace(secret.as_str(), MASK)
+        });
+    }
+
+    result
+}
+
+/// 替换前后不是字母或数字的 `secret`
+fn replace_word(text: &str, secret: &str) -> String {
+    let mut replaced = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = rest.find(secret) {
+        let end = index + secret.len();
+        let before = rest[..index].chars().next_back().or_else(|| replaced.chars().next_back());
+        let after = rest[end..].chars().next();
+        replaced.push_str(&rest[..index]);
+        if before.is_some_and(char::is_alphanumeric) || after.is_some_and(char::is_alphanumeric) {
+            replaced.push_str(secret);
+        } else {
+            replaced.push_str(MASK);
+        }
+        rest = &rest[end..];
+    }
+    replaced.push_str(rest);
+    replaced
+}
+
+/// 将前缀之后直到空白、引号或分隔符的内容替换为 `MASK`
+fn mask_after_prefix(text: &str, prefix: &str) -> String {
+    let mut masked = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = rest.find(prefix) {
+        let value_start = index + prefix.len();
+        masked.push_str(&rest[..value_start]);
+        let value = &rest[value_start..];
+        let value_end = value
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | ',' | '&' | ')' | '}'))
+            .unwrap_or(value.len());
+        if value_end > 0 {
+            masked.push_str(MASK);
+        }
+        rest = &value[value_end..];
+    }
+    masked.push_str(rest);
+    masked
+}
+
+/// 在写入前隐藏敏感内容的日志输出
+///
+/// fmt 层每条日志只调用一次 `write`，因此敏感内容不会被拆分到两次写入中。
+pub struct RedactingMakeWriter<M>(pub M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingMakeWriter<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+}
+
+pub struct RedactingWriter<W>(W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        self.0.write_all(redact(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// 收集日志输出的内存缓冲区
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Buffer {
+        type Writer = Buffer;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn log_output_never_contains_token() {
+        let token = "Xq3vR8tLm2Kp9WzA7bYc";
+        let unregistered = "Hn5sD1fGj6Qe0RuT4iOa";
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(RedactingMakeWriter(buffer.clone()))
+            .with_ansi(false)
+            .with_max_level(tracing::Level::DEBUG)
+            .finish();
+
+        register(token);
+        register("示例召唤师#1234");
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("成功获取LCU认证信息: port=51234, token={}", token);
+            tracing::info!(token = %token, "structured field");
+            tracing::debug!(
+                "PowerShell输出: \"C:/Riot Games/LeagueClientUx.exe\" --app-port=51234 --remoting-auth-token={} --locale=zh_CN",
+                unregistered
+            );
+            tracing::info!("成功获取召唤师信息: {}", "示例召唤师#1234");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(!output.contains(token), "{}", output);
+        assert!(!output.contains(unregistered), "{}", output);
+        assert!(!output.contains("示例召唤师"), "{}", output);
+        assert!(output.contains("--app-port=51234"));
+        assert!(output.contains("--locale=zh_CN"));
+    }
+
+    #[test]
+    fn short_values_are_not_registered() {
+        register("a");
+        assert_eq!(redact("a cat"), "a cat");
+    }
+
+    #[test]
+    fn short_names_are_only_masked_as_whole_words() {
+        register("Zed");
+        register("小明");
+        assert_eq!(redact("召唤师: Zed, 等级 30"), "召唤师: ***, 等级 30");
+        assert_eq!(redact("Zedd ZedZed Zed"), "Zedd ZedZed ***");
+        assert_eq!(redact("召唤师 小明"), "召唤师 ***");
+        assert_eq!(redact("小明天见"), "小明天见");
+    }
+}
@@ -1,12 +1,14 @@
 import React, { memo, useMemo } from 'react';
-import { SummonerInfo } from '../types';
+import { PlayerProfile, SummonerInfo } from '../types';
 import { calculateXpProgress } from '../utils/gameflow';
+import { formatRank } from '../utils/rank';
 
 interface AvatarSectionProps {
   summonerInfo?: SummonerInfo;
+  playerProfile?: PlayerProfile;
 }
 
-export const AvatarSection: React.FC<AvatarSectionProps> = memo(({ summonerInfo }) => {
+export const AvatarSection: React.FC<AvatarSectionProps> = memo(({ summonerInfo, playerProfile }) => {
   const xpProgress = useMemo(() => {
     return summonerInfo 
       ? calculateXpProgress(summonerInfo.xp_since_last_level, summonerInfo.xp_until_next_level)
@@ -19,6 +21,11 @@ export const AvatarSection: React.FC<AvatarSectionProps> = memo(({ summonerInfo
       : '/icon.png';
   }, [summonerInfo?.profile_icon_id]);
 
+  // 优先显示单双排段位，未定级时显示灵活排位
+  const rankText = useMemo(() => {
+    return formatRank(playerProfile?.solo_rank) || formatRank(playerProfile?.flex_rank);
+  }, [playerProfile?.solo_rank, playerProfile?.flex_rank]);
+
   const strokeDashoffset = useMemo(() => {
     const circumference = 2 * Math.PI * 30;
     return circumference * (1 - xpProgress / 100);
@@ -60,6 +67,11 @@ export const AvatarSection: React.FC<AvatarSectionProps> = memo(({ summonerInfo
           {summonerInfo ? summonerInfo.summoner_level : '等级'}
         </div>
       </div>
+      {rankText && (
+        <div className="avatar-rank" title={playerProfile?.challenge_title}>
+          {rankText}
+        </div>
+      )}
     </div>
   );
 });
\ No newline at end of file
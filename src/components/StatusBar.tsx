@@ -12,7 +12,10 @@ export const StatusBar: React.FC<StatusBarProps> = memo(({ appState }) => {
   
   return (
     <div className="status-bar" data-tauri-drag-region>
-      <AvatarSection summonerInfo={appState.summoner_info} />
+      <AvatarSection 
+        summonerInfo={appState.summoner_info} 
+        playerProfile={appState.player_profile} 
+      />
       <InfoPanel 
         summonerInfo={appState.summoner_info} 
         gameflowPhase={appState.gameflow_phase} 
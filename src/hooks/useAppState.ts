@@ -2,7 +2,7 @@ import { useState, useEffect, useCallback, useRef } from 'react';
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
-import { AppState } from '../types';
+import { AppState, PlayerProfile } from '../types';
 
 export const useAppState = () => {
   const [appState, setAppState] = useState<AppState>({
@@ -99,6 +99,33 @@ export const useAppState = () => {
       }));
     });
 
+    // 监听玩家资料更新
+    const unlistenPlayerProfile = listen('player-profile-updated', (event) => {
+      if (!isActiveRef.current) return;
+      
+      setAppState(prev => ({
+        ...prev,
+        player_profile: event.payload as PlayerProfile
+      }));
+    });
+
+    // 监听配置保存失败
+    const unlistenConfigSaveFailed = listen('config-save-failed', (event) => {
+      console.error('保存配置失败:', event.payload);
+    });
+
+    // 后台状态（开关、配置档案、重新加载的配置等）变化后推送完整状态
+    const unlistenStateChanged = listen<AppState>('state-changed', (event) => {
+      if (!isActiveRef.current) return;
+      
+      setAppState(prev => {
+        if (JSON.stringify(prev) !== JSON.stringify(event.payload)) {
+          return event.payload;
+        }
+        return prev;
+      });
+    });
+
     // 监听窗口移动事件，保存位置
     const setupWindowListeners = async () => {
       const currentWindow = getCurrentWindow();
@@ -147,6 +174,9 @@ export const useAppState = () => {
       unlistenMatchAccepted.then(fn => fn());
       unlistenLcuStatus.then(fn => fn());
       unlistenSummonerInfo.then(fn => fn());
+      unlistenPlayerProfile.then(fn => fn());
+      unlistenConfigSaveFailed.then(fn => fn());
+      unlistenStateChanged.then(fn => fn());
       
       if (unlistenMoved) {
         unlistenMoved();
@@ -1,5 +1,6 @@
 // 应用状态接口定义
 export interface SummonerInfo {
+  puuid: string;
   display_name: string;
   summoner_level: number;
   profile_icon_id: number;
@@ -7,12 +8,59 @@ export interface SummonerInfo {
   xp_until_next_level: number;
 }
 
+export interface RankedQueueStats {
+  queue_type: string;
+  tier: string;
+  division: string;
+  league_points: number;
+  wins: number;
+  losses: number;
+}
+
+export interface ChampionMastery {
+  champion_id: number;
+  champion_level: number;
+  champion_points: number;
+}
+
+export interface PlayerProfile {
+  solo_rank?: RankedQueueStats;
+  flex_rank?: RankedQueueStats;
+  top_masteries: ChampionMastery[];
+  honor_level?: number;
+  challenge_title?: string;
+}
+
+export interface SessionSummary {
+  date: string;
+  games: number;
+  wins: number;
+  losses: number;
+  net_lp: number;
+  promotions: number;
+  demotions: number;
+  queue_seconds: number;
+  game_seconds: number;
+}
+
 export interface AppState {
   mouse_through: boolean;
   auto_accept: boolean;
   gameflow_phase: string;
   lcu_connected: boolean;
   summoner_info?: SummonerInfo;
+  player_profile?: PlayerProfile;
+  session_summary?: SessionSummary;
+  active_profile?: string;
+  background?: BackgroundHealth;
+}
+
+// 后台任务健康状况
+export interface BackgroundHealth {
+  status: 'not_started' | 'running' | 'stopped';
+  restarts: number;
+  started_at?: number;
+  last_check_at?: number;
 }
 
 // 游戏流程阶段类型
@@ -39,4 +87,15 @@ export interface MatchAcceptedEvent {
 
 export interface WindowMoveEvent {
   payload: { x: number; y: number };
-}
\ No newline at end of file
+}
+// 配置包导入预览
+export interface ConfigChange {
+  path: string;
+  before: unknown;
+  after: unknown;
+}
+
+export interface ImportPreview {
+  changes: ConfigChange[];
+  rejected: string[];
+}
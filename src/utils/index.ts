@@ -1 +1,2 @@
-export * from './gameflow';
\ No newline at end of file
+export * from './gameflow';
+export * from './rank';
\ No newline at end of file
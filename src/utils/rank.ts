@@ -0,0 +1,28 @@
+import { RankedQueueStats } from '../types';
+
+// 段位显示名称映射
+const tierDisplayNames: Record<string, string> = {
+  'IRON': '坚韧黑铁',
+  'BRONZE': '英勇黄铜',
+  'SILVER': '不屈白银',
+  'GOLD': '荣耀黄金',
+  'PLATINUM': '华贵铂金',
+  'EMERALD': '流光翡翠',
+  'DIAMOND': '璀璨钻石',
+  'MASTER': '超凡大师',
+  'GRANDMASTER': '傲世宗师',
+  'CHALLENGER': '最强王者'
+};
+
+// 大师及以上段位没有小段
+const apexTiers = ['MASTER', 'GRANDMASTER', 'CHALLENGER'];
+
+// 格式化段位，未定级时返回空字符串
+export const formatRank = (rank?: RankedQueueStats): string => {
+  if (!rank || !tierDisplayNames[rank.tier]) return '';
+  const tier = tierDisplayNames[rank.tier];
+  if (apexTiers.includes(rank.tier)) {
+    return `${tier} ${rank.league_points}LP`;
+  }
+  return `${tier} ${rank.division} ${rank.league_points}LP`;
+};
\ No newline at end of file